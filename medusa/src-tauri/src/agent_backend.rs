@@ -0,0 +1,449 @@
+//! Agent Backends - Pluggable CLI agents for tasks
+//!
+//! A backend describes how to launch a particular coding agent inside a task
+//! worktree, how to talk to it over stdin and how to interpret what it prints.
+//! The agent supervisor and `TaskAgentManager` own the process plumbing (pipes,
+//! session files, events) and drive every backend through the `AgentBackend` trait.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::process::{Child, Command, Stdio};
+use tracing::info;
+
+/// Identifier of the default backend (Claude Code CLI)
+pub const CLAUDE_BACKEND: &str = "claude";
+
+/// Identifier of the generic command backend
+pub const COMMAND_BACKEND: &str = "command";
+
+/// Everything a backend needs to launch (or resume) an agent
+#[derive(Debug, Clone)]
+pub struct SpawnRequest<'a> {
+    pub task_id: &'a str,
+    pub worktree_path: &'a str,
+    /// Session to resume, if the backend supports resuming
+    pub resume_session_id: Option<&'a str>,
+    /// The task prompt (only used when starting a fresh session)
+    pub prompt: &'a str,
+    /// Model to run, if the backend lets you choose one
+    pub model: Option<&'a str>,
+    /// Extra command-line arguments for the agent CLI
+    pub extra_args: &'a [String],
+}
+
+/// Per-run agent settings, e.g. for one of several attempts at a task
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LaunchProfile {
+    /// Backend ID (None: the task's or the default backend)
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// Event produced by a backend while interpreting agent output
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentEvent {
+    /// A line for the output stream (persisted to the session file and shown in the UI)
+    Output(String),
+    /// The agent reported a session ID that can be used to resume it later
+    SessionStarted(String),
+}
+
+/// A coding agent that can be run against a task worktree
+pub trait AgentBackend: Send + Sync {
+    /// Stable identifier stored with the task (e.g. "claude")
+    fn id(&self) -> &'static str;
+
+    /// Pre-flight check that the agent CLI is available
+    fn check_installed(&self) -> Result<()>;
+
+    /// Spawn the agent process in the worktree with piped stdio.
+    /// When `request.resume_session_id` is set, the backend should resume that session.
+    fn spawn(&self, request: &SpawnRequest) -> Result<Child>;
+
+    /// Whether `spawn` can resume a previous session
+    fn supports_resume(&self) -> bool;
+
+    /// Spawn a one-shot, read-only planning run for `request.prompt` that prints its
+    /// answer to stdout and exits (used to decompose tasks)
+    fn spawn_plan(&self, _request: &SpawnRequest) -> Result<Child> {
+        Err(anyhow::anyhow!("The {} agent backend can't run in plan-only mode", self.id()))
+    }
+
+    /// Extract the answer from a plan-only run's stdout
+    fn plan_result(&self, stdout: &str) -> String {
+        stdout.to_string()
+    }
+
+    /// The configured shell command, for backends that have one
+    fn command(&self) -> Option<&str> {
+        None
+    }
+
+    /// Encode a user message for the agent's stdin (None if the agent takes no input)
+    fn encode_message(&self, message: &str) -> Option<String>;
+
+    /// The first stdin message for a fresh session (None if the prompt was passed at spawn)
+    fn initial_input(&self, prompt: &str) -> Option<String> {
+        if prompt.is_empty() {
+            None
+        } else {
+            self.encode_message(prompt)
+        }
+    }
+
+    /// Interpret one line of stdout
+    fn parse_output(&self, line: &str) -> Vec<AgentEvent>;
+
+    /// Total cost in USD reported in a session's output, if the agent reports it
+    fn total_cost(&self, _output_lines: &[String]) -> Option<f64> {
+        None
+    }
+
+    /// Whether an output line marks the end of the agent's turn (it's waiting for input)
+    fn is_turn_end(&self, _line: &str) -> bool {
+        false
+    }
+
+    /// Stop a running agent process
+    fn stop(&self, pid: u32) -> Result<()> {
+        #[cfg(unix)]
+        {
+            let _ = Command::new("kill")
+                .args(["-TERM", &pid.to_string()])
+                .output();
+        }
+
+        #[cfg(windows)]
+        {
+            let _ = Command::new("taskkill")
+                .args(["/PID", &pid.to_string(), "/F"])
+                .output();
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolve a backend by ID. `agent_command` is required for the command backend.
+pub fn backend_for(id: &str, agent_command: Option<&str>) -> Result<Box<dyn AgentBackend>> {
+    match id {
+        CLAUDE_BACKEND => Ok(Box::new(ClaudeBackend)),
+        COMMAND_BACKEND => {
+            let command = agent_command
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .ok_or_else(|| anyhow::anyhow!(
+                    "The command agent backend needs a command. Set one in Settings."
+                ))?;
+            Ok(Box::new(CommandBackend::new(command.to_string())))
+        }
+        other => Err(anyhow::anyhow!("Unknown agent backend: {}", other)),
+    }
+}
+
+/// Get the user's home directory for agent processes
+fn home_dir() -> String {
+    std::env::var("HOME").unwrap_or_else(|_| {
+        // Fallback: try to get home from /etc/passwd or use a default
+        std::env::var("USER")
+            .map(|u| format!("/Users/{}", u))
+            .unwrap_or_else(|_| "/Users".to_string())
+    })
+}
+
+/// Build a zsh command with nvm sourced, so CLIs installed through npm are on PATH
+pub(crate) fn shell_command(script: &str) -> Command {
+    let shell_cmd = format!(
+        "export NVM_DIR=\"$HOME/.nvm\"; [ -s \"$NVM_DIR/nvm.sh\" ] && . \"$NVM_DIR/nvm.sh\"; {}",
+        script
+    );
+
+    let mut cmd = Command::new("/bin/zsh");
+    cmd.args(["-c", &shell_cmd]).env("HOME", home_dir());
+    cmd
+}
+
+/// Quote a string for safe use as a single shell word
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Shell-quoted extra arguments, each preceded by a space
+fn quoted_args(args: &[String]) -> String {
+    args.iter().map(|a| format!(" {}", shell_quote(a))).collect()
+}
+
+/// Spawn a shell script in the worktree with all stdio piped
+fn spawn_in_worktree(script: &str, request: &SpawnRequest, extra_env: &[(&str, &str)]) -> Result<Child> {
+    info!("Running via shell: {}", script);
+
+    let mut cmd = shell_command(script);
+    cmd.env("MEDUSA_TASK_ID", request.task_id) // Pass task ID so hook script knows this is a task agent
+        .current_dir(request.worktree_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    for (key, value) in extra_env {
+        cmd.env(key, value);
+    }
+
+    Ok(cmd.spawn()?)
+}
+
+// ============== Claude Code ==============
+
+/// Claude Code CLI speaking the stream-json protocol
+pub struct ClaudeBackend;
+
+impl AgentBackend for ClaudeBackend {
+    fn id(&self) -> &'static str {
+        CLAUDE_BACKEND
+    }
+
+    fn check_installed(&self) -> Result<()> {
+        let output = shell_command("which claude").output();
+
+        match output {
+            Ok(result) => {
+                if result.status.success() {
+                    let path = String::from_utf8_lossy(&result.stdout);
+                    info!("Found Claude CLI at: {}", path.trim());
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!(
+                        "Claude Code CLI not found. Please install it first:\n\
+                        npm install -g @anthropic-ai/claude-code\n\n\
+                        Or visit: https://docs.anthropic.com/en/docs/claude-code"
+                    ))
+                }
+            }
+            Err(e) => {
+                Err(anyhow::anyhow!(
+                    "Failed to check for Claude Code CLI: {}. Please ensure it's installed.", e
+                ))
+            }
+        }
+    }
+
+    fn spawn(&self, request: &SpawnRequest) -> Result<Child> {
+        let mut claude_args = String::new();
+        if let Some(session_id) = request.resume_session_id {
+            info!("Resuming session {} for task {}", session_id, request.task_id);
+            claude_args.push_str(&format!("--resume {} ", shell_quote(session_id)));
+        }
+        if let Some(model) = request.model {
+            claude_args.push_str(&format!("--model {} ", shell_quote(model)));
+        }
+        claude_args.push_str(
+            "--verbose --output-format stream-json --input-format stream-json --dangerously-skip-permissions",
+        );
+        claude_args.push_str(&quoted_args(request.extra_args));
+
+        spawn_in_worktree(&format!("claude {}", claude_args), request, &[])
+    }
+
+    fn supports_resume(&self) -> bool {
+        true
+    }
+
+    fn spawn_plan(&self, request: &SpawnRequest) -> Result<Child> {
+        let script = format!(
+            "claude -p {} --permission-mode plan --output-format json",
+            shell_quote(request.prompt)
+        );
+        spawn_in_worktree(&script, request, &[])
+    }
+
+    fn plan_result(&self, stdout: &str) -> String {
+        // --output-format json prints a single object with the final answer in "result"
+        serde_json::from_str::<serde_json::Value>(stdout.trim())
+            .ok()
+            .and_then(|json| json.get("result").and_then(|r| r.as_str()).map(String::from))
+            .unwrap_or_else(|| stdout.to_string())
+    }
+
+    fn encode_message(&self, message: &str) -> Option<String> {
+        // Format message as JSON for stream-json input format
+        let json_message = serde_json::json!({
+            "type": "user",
+            "message": {
+                "role": "user",
+                "content": message
+            }
+        });
+        Some(json_message.to_string())
+    }
+
+    fn parse_output(&self, line: &str) -> Vec<AgentEvent> {
+        let mut events = Vec::new();
+
+        // Extract session_id from the init message
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+            if json.get("type").and_then(|t| t.as_str()) == Some("system")
+                && json.get("subtype").and_then(|t| t.as_str()) == Some("init")
+            {
+                if let Some(session_id) = json.get("session_id").and_then(|s| s.as_str()) {
+                    events.push(AgentEvent::SessionStarted(session_id.to_string()));
+                }
+            }
+        }
+
+        events.push(AgentEvent::Output(line.to_string()));
+        events
+    }
+
+    fn total_cost(&self, output_lines: &[String]) -> Option<f64> {
+        // Every turn ends with a "result" message carrying that turn's cost
+        let costs: Vec<f64> = output_lines
+            .iter()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|json| json.get("type").and_then(|t| t.as_str()) == Some("result"))
+            .filter_map(|json| json.get("total_cost_usd").and_then(|c| c.as_f64()))
+            .collect();
+
+        if costs.is_empty() {
+            None
+        } else {
+            Some(costs.iter().sum())
+        }
+    }
+
+    fn is_turn_end(&self, line: &str) -> bool {
+        serde_json::from_str::<serde_json::Value>(line)
+            .ok()
+            .and_then(|json| json.get("type").and_then(|t| t.as_str()).map(|t| t == "result"))
+            .unwrap_or(false)
+    }
+}
+
+// ============== Generic command ==============
+
+/// Any CLI agent run through the shell. Stdout is treated as plain text.
+///
+/// The command may contain a `{prompt}` placeholder, which is replaced with the
+/// shell-quoted task prompt. Without a placeholder the prompt is written to stdin.
+/// The prompt is also available as `$MEDUSA_PROMPT` and a launch profile's model as
+/// `$MEDUSA_MODEL`; its extra arguments are appended to the command.
+pub struct CommandBackend {
+    command: String,
+}
+
+impl CommandBackend {
+    pub fn new(command: String) -> Self {
+        Self { command }
+    }
+
+    fn takes_prompt_argument(&self) -> bool {
+        self.command.contains("{prompt}")
+    }
+}
+
+impl AgentBackend for CommandBackend {
+    fn id(&self) -> &'static str {
+        COMMAND_BACKEND
+    }
+
+    fn check_installed(&self) -> Result<()> {
+        let program = self.command.split_whitespace().next().unwrap_or_default();
+        let output = shell_command(&format!("command -v {}", shell_quote(program))).output()?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("Agent command not found: {}", program));
+        }
+        Ok(())
+    }
+
+    fn spawn(&self, request: &SpawnRequest) -> Result<Child> {
+        let script = self.command.replace("{prompt}", &shell_quote(request.prompt))
+            + &quoted_args(request.extra_args);
+        let mut env = vec![("MEDUSA_PROMPT", request.prompt)];
+        if let Some(model) = request.model {
+            env.push(("MEDUSA_MODEL", model));
+        }
+        spawn_in_worktree(&script, request, &env)
+    }
+
+    fn supports_resume(&self) -> bool {
+        false
+    }
+
+    fn command(&self) -> Option<&str> {
+        Some(&self.command)
+    }
+
+    fn encode_message(&self, message: &str) -> Option<String> {
+        Some(message.to_string())
+    }
+
+    fn initial_input(&self, prompt: &str) -> Option<String> {
+        if prompt.is_empty() || self.takes_prompt_argument() {
+            None
+        } else {
+            self.encode_message(prompt)
+        }
+    }
+
+    fn parse_output(&self, line: &str) -> Vec<AgentEvent> {
+        // Wrap plain text as an assistant message so it renders like any other agent output
+        let json_line = serde_json::json!({
+            "type": "assistant",
+            "message": {
+                "role": "assistant",
+                "content": [{ "type": "text", "text": line }]
+            }
+        });
+        vec![AgentEvent::Output(json_line.to_string())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claude_backend_captures_session_id() {
+        let line = r#"{"type":"system","subtype":"init","session_id":"abc-123","cwd":"/tmp"}"#;
+        let events = ClaudeBackend.parse_output(line);
+
+        assert_eq!(events[0], AgentEvent::SessionStarted("abc-123".to_string()));
+        assert_eq!(events[1], AgentEvent::Output(line.to_string()));
+    }
+
+    #[test]
+    fn test_command_backend_wraps_text() {
+        let backend = CommandBackend::new("my-agent --yes".to_string());
+        let events = backend.parse_output("hello");
+
+        let AgentEvent::Output(line) = &events[0] else {
+            panic!("expected output event");
+        };
+        let json: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(json["message"]["content"][0]["text"], "hello");
+        assert_eq!(backend.initial_input("do it"), Some("do it".to_string()));
+
+        let backend = CommandBackend::new("my-agent {prompt}".to_string());
+        assert_eq!(backend.initial_input("do it"), None);
+    }
+
+    #[test]
+    fn test_claude_backend_sums_turn_costs() {
+        let lines = vec![
+            r#"{"type":"assistant","message":{"content":[]}}"#.to_string(),
+            r#"{"type":"result","subtype":"success","total_cost_usd":0.25}"#.to_string(),
+            r#"{"type":"result","subtype":"success","total_cost_usd":0.5}"#.to_string(),
+        ];
+
+        assert_eq!(ClaudeBackend.total_cost(&lines), Some(0.75));
+        assert_eq!(ClaudeBackend.total_cost(&lines[..1]), None);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
@@ -0,0 +1,199 @@
+//! Task Attempts - Several agents racing on the same task
+//!
+//! A task can be started as N parallel attempts, each with its own worktree,
+//! branch (`medusa/task-<id>-a`, `-b`, ...) and optionally its own launch
+//! profile. An attempt runs as an ordinary agent whose agent ID is
+//! `<task id>--<label>`, so the agent manager, supervisor and session files
+//! need no special handling. Attempts only live until one of them is merged;
+//! the others are then discarded.
+
+use crate::agent_backend::LaunchProfile;
+use crate::commands::init_tasks_db;
+use crate::git::DiffStats;
+use crate::task_agent::TaskAgentStatus;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Separator between the task ID and the attempt label in an agent ID
+const ATTEMPT_SEPARATOR: &str = "--";
+
+/// Most attempts a task can be started with at once
+pub const MAX_ATTEMPTS: usize = 8;
+
+/// One of several parallel attempts at a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskAttempt {
+    /// Agent ID of the attempt (`<task id>--<label>`)
+    pub id: String,
+    pub task_id: String,
+    /// "a", "b", ...
+    pub label: String,
+    pub profile: LaunchProfile,
+    pub backend: String, // The backend the attempt actually runs with
+    pub branch: String,
+    pub worktree_path: String,
+    pub base_commit: String,
+    pub base_branch: String,
+    pub agent_pid: Option<u32>,
+    pub started_at: u64,
+}
+
+/// An attempt with what the reviewer needs to pick a winner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttemptComparison {
+    pub attempt: TaskAttempt,
+    /// Live agent status (None when the app isn't tracking the agent)
+    pub status: Option<TaskAgentStatus>,
+    pub diff: DiffStats,
+    pub changed_files: Vec<String>,
+    pub cost_usd: Option<f64>,
+}
+
+/// Label of the attempt at `index` ("a", "b", ...)
+pub fn attempt_label(index: usize) -> String {
+    ((b'a' + index as u8) as char).to_string()
+}
+
+/// Agent ID of a task's attempt
+pub fn attempt_agent_id(task_id: &str, label: &str) -> String {
+    format!("{}{}{}", task_id, ATTEMPT_SEPARATOR, label)
+}
+
+/// Split an agent ID into the task ID and, for attempts, the attempt label
+pub fn split_agent_id(agent_id: &str) -> (&str, Option<&str>) {
+    match agent_id.split_once(ATTEMPT_SEPARATOR) {
+        Some((task_id, label)) if !label.is_empty() => (task_id, Some(label)),
+        _ => (agent_id, None),
+    }
+}
+
+/// Create the attempts table if needed
+pub(crate) fn init_attempts_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS task_attempts (
+            id TEXT PRIMARY KEY,
+            task_id TEXT NOT NULL,
+            label TEXT NOT NULL,
+            profile TEXT NOT NULL,
+            backend TEXT NOT NULL,
+            branch TEXT NOT NULL,
+            worktree_path TEXT NOT NULL,
+            base_commit TEXT NOT NULL,
+            base_branch TEXT NOT NULL,
+            agent_pid INTEGER,
+            started_at INTEGER NOT NULL
+        )",
+        [],
+    ).map_err(|e| format!("Failed to create task_attempts table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_task_attempts_task ON task_attempts(task_id)",
+        [],
+    ).ok();
+
+    Ok(())
+}
+
+const ATTEMPT_COLUMNS: &str =
+    "id, task_id, label, profile, backend, branch, worktree_path, base_commit, base_branch, agent_pid, started_at";
+
+fn attempt_from_row(row: &rusqlite::Row) -> rusqlite::Result<TaskAttempt> {
+    let profile: String = row.get(3)?;
+    Ok(TaskAttempt {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        label: row.get(2)?,
+        profile: serde_json::from_str(&profile).unwrap_or_default(),
+        backend: row.get(4)?,
+        branch: row.get(5)?,
+        worktree_path: row.get(6)?,
+        base_commit: row.get(7)?,
+        base_branch: row.get(8)?,
+        agent_pid: row.get(9)?,
+        started_at: row.get(10)?,
+    })
+}
+
+fn query_attempts(sql: &str, args: impl rusqlite::Params) -> Result<Vec<TaskAttempt>, String> {
+    let conn = init_tasks_db()?;
+    let mut stmt = conn.prepare(sql)
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let attempts = stmt.query_map(args, attempt_from_row)
+        .map_err(|e| format!("Failed to query attempts: {}", e))?
+        .filter_map(|a| a.ok())
+        .collect();
+
+    Ok(attempts)
+}
+
+/// A task's attempts, in label order
+pub fn load_attempts(task_id: &str) -> Result<Vec<TaskAttempt>, String> {
+    query_attempts(
+        &format!("SELECT {} FROM task_attempts WHERE task_id = ?1 ORDER BY label", ATTEMPT_COLUMNS),
+        params![task_id],
+    )
+}
+
+/// Attempts of all tasks
+pub fn load_all_attempts() -> Result<Vec<TaskAttempt>, String> {
+    query_attempts(&format!("SELECT {} FROM task_attempts", ATTEMPT_COLUMNS), [])
+}
+
+/// Record a started attempt
+pub fn insert_attempt(attempt: &TaskAttempt) -> Result<(), String> {
+    let conn = init_tasks_db()?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO task_attempts ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            ATTEMPT_COLUMNS
+        ),
+        params![
+            attempt.id,
+            attempt.task_id,
+            attempt.label,
+            serde_json::to_string(&attempt.profile).unwrap_or_default(),
+            attempt.backend,
+            attempt.branch,
+            attempt.worktree_path,
+            attempt.base_commit,
+            attempt.base_branch,
+            attempt.agent_pid,
+            attempt.started_at
+        ],
+    ).map_err(|e| format!("Failed to save attempt: {}", e))?;
+    Ok(())
+}
+
+/// Clear an attempt's agent PID once its agent is stopped or gone
+pub fn clear_attempt_pid(attempt_id: &str) -> Result<(), String> {
+    let conn = init_tasks_db()?;
+    conn.execute(
+        "UPDATE task_attempts SET agent_pid = NULL WHERE id = ?1",
+        params![attempt_id],
+    ).map_err(|e| format!("Failed to update attempt: {}", e))?;
+    Ok(())
+}
+
+/// Forget an attempt
+pub fn delete_attempt(attempt_id: &str) -> Result<(), String> {
+    let conn = init_tasks_db()?;
+    conn.execute("DELETE FROM task_attempts WHERE id = ?1", params![attempt_id])
+        .map_err(|e| format!("Failed to delete attempt: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attempt_agent_ids() {
+        let task_id = "0b5c7e2a-9f1d-4c3b-8a6e-1d2f3a4b5c6d";
+        let agent_id = attempt_agent_id(task_id, &attempt_label(1));
+
+        assert_eq!(agent_id, "0b5c7e2a-9f1d-4c3b-8a6e-1d2f3a4b5c6d--b");
+        assert_eq!(split_agent_id(&agent_id), (task_id, Some("b")));
+        assert_eq!(split_agent_id(task_id), (task_id, None));
+    }
+}
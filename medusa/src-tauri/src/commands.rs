@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
+use tauri::Emitter;
 use tracing::info;
 use uuid::Uuid;
 
@@ -179,7 +180,7 @@ fn get_queue() -> &'static Mutex<PlanQueue> {
 }
 
 /// Get current timestamp in seconds
-fn now() -> u64 {
+pub(crate) fn now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
@@ -531,12 +532,92 @@ pub async fn open_in_obsidian(vault_name: String, filename: String) -> Result<()
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MedusaSettings {
     pub hook_timeout_minutes: u32,
+    /// Default agent backend for new tasks ("claude" or "command")
+    #[serde(default = "default_agent_backend")]
+    pub agent_backend: String,
+    /// Shell command for the "command" backend (may contain a {prompt} placeholder)
+    #[serde(default)]
+    pub agent_command: Option<String>,
+    /// Maximum agents running at once across all projects
+    #[serde(default = "default_max_concurrent_agents")]
+    pub max_concurrent_agents: usize,
+    /// Maximum agents running at once in one project (None: only the global limit applies)
+    #[serde(default)]
+    pub max_agents_per_project: Option<usize>,
+    /// Per-project overrides of `max_agents_per_project`, keyed by project path
+    #[serde(default)]
+    pub project_agent_limits: HashMap<String, usize>,
+    /// Per-project settings, keyed by project path
+    #[serde(default)]
+    pub project_settings: HashMap<String, ProjectSettings>,
+}
+
+/// Settings that apply to one project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectSettings {
+    /// Shell commands that must pass before a task enters Review (e.g. "cargo test")
+    #[serde(default)]
+    pub verify_commands: Vec<String>,
+    /// How long each verification command may run
+    #[serde(default = "default_verify_timeout_secs")]
+    pub verify_timeout_secs: u64,
+    /// How often a failed verification is sent back to the agent to fix (0: never)
+    #[serde(default)]
+    pub verify_fix_retries: u32,
+}
+
+fn default_verify_timeout_secs() -> u64 {
+    600
+}
+
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            verify_commands: Vec::new(),
+            verify_timeout_secs: default_verify_timeout_secs(),
+            verify_fix_retries: 0,
+        }
+    }
+}
+
+fn default_agent_backend() -> String {
+    crate::agent_backend::CLAUDE_BACKEND.to_string()
+}
+
+fn default_max_concurrent_agents() -> usize {
+    3
+}
+
+impl MedusaSettings {
+    /// Concurrency limits for agents in a project (every limit allows at least one agent)
+    pub(crate) fn agent_limits(&self, project_path: &str) -> crate::task_agent::AgentLimits {
+        let max_total = self.max_concurrent_agents.max(1);
+        let max_per_project = self.project_agent_limits
+            .get(project_path)
+            .copied()
+            .or(self.max_agents_per_project)
+            .unwrap_or(max_total)
+            .max(1);
+
+        crate::task_agent::AgentLimits { max_total, max_per_project }
+    }
+
+    /// Settings for a project (defaults when it has none)
+    pub(crate) fn project(&self, project_path: &str) -> ProjectSettings {
+        self.project_settings.get(project_path).cloned().unwrap_or_default()
+    }
 }
 
 impl Default for MedusaSettings {
     fn default() -> Self {
         Self {
             hook_timeout_minutes: 10, // 10 minutes default
+            agent_backend: default_agent_backend(),
+            agent_command: None,
+            max_concurrent_agents: default_max_concurrent_agents(),
+            max_agents_per_project: None,
+            project_agent_limits: HashMap::new(),
+            project_settings: HashMap::new(),
         }
     }
 }
@@ -549,23 +630,28 @@ fn get_settings_file() -> PathBuf {
     settings_dir.join("settings.json")
 }
 
-/// Get current settings
-#[tauri::command]
-pub async fn get_settings() -> Result<MedusaSettings, String> {
+/// Load settings from disk, falling back to defaults
+pub(crate) fn load_settings() -> MedusaSettings {
     let settings_file = get_settings_file();
     if settings_file.exists() {
         if let Ok(content) = fs::read_to_string(&settings_file) {
             if let Ok(settings) = serde_json::from_str(&content) {
-                return Ok(settings);
+                return settings;
             }
         }
     }
-    Ok(MedusaSettings::default())
+    MedusaSettings::default()
+}
+
+/// Get current settings
+#[tauri::command]
+pub async fn get_settings() -> Result<MedusaSettings, String> {
+    Ok(load_settings())
 }
 
 /// Save settings
 #[tauri::command]
-pub async fn save_settings(settings: MedusaSettings) -> Result<(), String> {
+pub async fn save_settings(app: tauri::AppHandle, settings: MedusaSettings) -> Result<(), String> {
     info!("Saving settings: {:?}", settings);
     let settings_file = get_settings_file();
     let content = serde_json::to_string_pretty(&settings)
@@ -578,6 +664,9 @@ pub async fn save_settings(settings: MedusaSettings) -> Result<(), String> {
         tracing::warn!("Failed to update hook config after settings save: {}", e);
     }
 
+    // Limits may have been raised - start queued tasks that now fit
+    std::thread::spawn(move || drain_agent_queue(app));
+
     Ok(())
 }
 
@@ -864,10 +953,13 @@ pub async fn get_history_count() -> Result<u32, String> {
 
 // ============== Kanban Task Management (Medusa 2.0) ==============
 
+use crate::verification::{VerificationEvent, VerificationResult, VerificationStatus};
+
 /// Task status in the Kanban board
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskStatus {
     Backlog,
+    Queued, // Waiting for a free agent slot
     InProgress,
     Review,
     Done,
@@ -877,6 +969,7 @@ impl std::fmt::Display for TaskStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TaskStatus::Backlog => write!(f, "Backlog"),
+            TaskStatus::Queued => write!(f, "Queued"),
             TaskStatus::InProgress => write!(f, "InProgress"),
             TaskStatus::Review => write!(f, "Review"),
             TaskStatus::Done => write!(f, "Done"),
@@ -889,6 +982,7 @@ impl std::str::FromStr for TaskStatus {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "Backlog" => Ok(TaskStatus::Backlog),
+            "Queued" => Ok(TaskStatus::Queued),
             "InProgress" => Ok(TaskStatus::InProgress),
             "Review" => Ok(TaskStatus::Review),
             "Done" => Ok(TaskStatus::Done),
@@ -912,8 +1006,15 @@ pub struct KanbanTask {
     pub plan_id: Option<String>,
     pub agent_pid: Option<u32>,
     pub session_id: Option<String>,  // Claude Code session ID for resuming
+    pub agent_backend: Option<String>, // The agent backend the task runs with (resumes use the same one)
     pub base_commit: Option<String>, // The commit the worktree was created from (for accurate diffs)
     pub base_branch: Option<String>, // The branch the task was created from (for merging back)
+    pub priority: i64, // Higher priorities leave the run queue first
+    pub queued_at: Option<u64>, // When the task entered the run queue
+    pub depends_on: Vec<String>, // Prerequisite task IDs that must be merged before this task starts
+    pub branch_from_dependency: bool, // Start from a prerequisite's task branch once it's in review
+    pub parent_id: Option<String>, // The task this one was split out of
+    pub verification_status: Option<VerificationStatus>, // Outcome of the last verification run
     pub started_at: Option<u64>,
     pub completed_at: Option<u64>,
     pub files_changed: Option<Vec<String>>,
@@ -928,6 +1029,9 @@ pub struct CreateTaskRequest {
     pub title: String,
     pub description: Option<String>,
     pub project_path: String,
+    pub priority: Option<i64>,
+    pub depends_on: Option<Vec<String>>,
+    pub branch_from_dependency: Option<bool>,
 }
 
 /// Request to update a task
@@ -939,6 +1043,9 @@ pub struct UpdateTaskRequest {
     pub status: Option<TaskStatus>,
     pub branch: Option<String>,
     pub plan_id: Option<String>,
+    pub priority: Option<i64>,
+    pub depends_on: Option<Vec<String>>,
+    pub branch_from_dependency: Option<bool>,
 }
 
 /// Get the path to the tasks database
@@ -951,7 +1058,7 @@ fn get_tasks_db_path() -> PathBuf {
 }
 
 /// Initialize tasks database with schema
-fn init_tasks_db() -> Result<Connection, String> {
+pub(crate) fn init_tasks_db() -> Result<Connection, String> {
     let db_path = get_tasks_db_path();
     let conn = Connection::open(&db_path)
         .map_err(|e| format!("Failed to open tasks database: {}", e))?;
@@ -996,6 +1103,56 @@ fn init_tasks_db() -> Result<Connection, String> {
         [],
     ).ok(); // Ignore error if column already exists
 
+    // Migration: add agent_backend column if it doesn't exist
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN agent_backend TEXT",
+        [],
+    ).ok(); // Ignore error if column already exists
+
+    // Migration: add run queue columns if they don't exist
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN priority INTEGER NOT NULL DEFAULT 0",
+        [],
+    ).ok(); // Ignore error if column already exists
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN queued_at INTEGER",
+        [],
+    ).ok();
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN queued_prompt TEXT",
+        [],
+    ).ok();
+
+    // Migration: add dependency columns if they don't exist
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN depends_on TEXT",
+        [],
+    ).ok(); // Ignore error if column already exists
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN branch_from_dependency INTEGER NOT NULL DEFAULT 0",
+        [],
+    ).ok();
+
+    // Migration: add parent_id column if it doesn't exist
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN parent_id TEXT",
+        [],
+    ).ok(); // Ignore error if column already exists
+
+    // Migration: add verification columns if they don't exist
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN verification_status TEXT",
+        [],
+    ).ok(); // Ignore error if column already exists
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN verification_result TEXT",
+        [],
+    ).ok();
+    conn.execute(
+        "ALTER TABLE kanban_tasks ADD COLUMN verification_retries INTEGER NOT NULL DEFAULT 0",
+        [],
+    ).ok();
+
     // Create indexes
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_kanban_tasks_status ON kanban_tasks(status)",
@@ -1007,16 +1164,64 @@ fn init_tasks_db() -> Result<Connection, String> {
         [],
     ).ok();
 
+    crate::attempts::init_attempts_table(&conn)?;
+
     Ok(conn)
 }
 
+/// Columns selected for a `KanbanTask`, in the order `task_from_row` reads them
+const TASK_COLUMNS: &str = "id, title, description, status, project_path, branch, worktree_path, plan_id,
+    agent_pid, session_id, base_commit, base_branch, started_at, completed_at, files_changed, diff_summary,
+    created_at, updated_at, agent_backend, priority, queued_at, depends_on, branch_from_dependency, parent_id,
+    verification_status";
+
+/// Map a row selected with `TASK_COLUMNS` to a `KanbanTask`
+fn task_from_row(row: &rusqlite::Row) -> rusqlite::Result<KanbanTask> {
+    let status_str: String = row.get(3)?;
+    let files_changed_str: Option<String> = row.get(14)?;
+    let files_changed = files_changed_str.and_then(|s| serde_json::from_str(&s).ok());
+    let depends_on_str: Option<String> = row.get(21)?;
+    let depends_on = depends_on_str.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default();
+    let verification_status: Option<String> = row.get(24)?;
+
+    Ok(KanbanTask {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        description: row.get(2)?,
+        status: status_str.parse().unwrap_or(TaskStatus::Backlog),
+        project_path: row.get(4)?,
+        branch: row.get(5)?,
+        worktree_path: row.get(6)?,
+        plan_id: row.get(7)?,
+        agent_pid: row.get(8)?,
+        session_id: row.get(9)?,
+        agent_backend: row.get(18)?,
+        base_commit: row.get(10)?,
+        base_branch: row.get(11)?,
+        priority: row.get(19)?,
+        queued_at: row.get(20)?,
+        depends_on,
+        branch_from_dependency: row.get(22)?,
+        parent_id: row.get(23)?,
+        verification_status: verification_status.as_deref().and_then(VerificationStatus::parse),
+        started_at: row.get(12)?,
+        completed_at: row.get(13)?,
+        files_changed,
+        diff_summary: row.get(15)?,
+        created_at: row.get(16)?,
+        updated_at: row.get(17)?,
+    })
+}
+
 /// Create a new task
 #[tauri::command]
 pub async fn create_task(request: CreateTaskRequest) -> Result<KanbanTask, String> {
     info!("Creating new task: {}", request.title);
 
-    let conn = init_tasks_db()?;
     let id = Uuid::new_v4().to_string();
+    let depends_on = request.depends_on.unwrap_or_default();
+    validate_dependencies(&id, &request.project_path, &depends_on)?;
+
     let now_ts = now();
 
     let task = KanbanTask {
@@ -1030,8 +1235,15 @@ pub async fn create_task(request: CreateTaskRequest) -> Result<KanbanTask, Strin
         plan_id: None,
         agent_pid: None,
         session_id: None,
+        agent_backend: None,
         base_commit: None,
         base_branch: None,
+        priority: request.priority.unwrap_or(0),
+        queued_at: None,
+        depends_on,
+        branch_from_dependency: request.branch_from_dependency.unwrap_or(false),
+        parent_id: None,
+        verification_status: None,
         started_at: None,
         completed_at: None,
         files_changed: None,
@@ -1040,55 +1252,51 @@ pub async fn create_task(request: CreateTaskRequest) -> Result<KanbanTask, Strin
         updated_at: now_ts,
     };
 
-    conn.execute(
-        "INSERT INTO kanban_tasks (id, title, description, status, project_path, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        params![task.id, task.title, task.description, task.status.to_string(), task.project_path, task.created_at, task.updated_at],
-    ).map_err(|e| format!("Failed to create task: {}", e))?;
+    insert_task(&init_tasks_db()?, &task)?;
 
     info!("Task {} created", id);
     Ok(task)
 }
 
+/// Insert a new task row
+fn insert_task(conn: &Connection, task: &KanbanTask) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO kanban_tasks (id, title, description, status, project_path, priority, depends_on, branch_from_dependency, parent_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            task.id,
+            task.title,
+            task.description,
+            task.status.to_string(),
+            task.project_path,
+            task.priority,
+            serde_json::to_string(&task.depends_on).unwrap_or_default(),
+            task.branch_from_dependency,
+            task.parent_id,
+            task.created_at,
+            task.updated_at
+        ],
+    ).map_err(|e| format!("Failed to create task: {}", e))?;
+
+    Ok(())
+}
+
 /// Get all tasks
 #[tauri::command]
 pub async fn get_all_tasks() -> Result<Vec<KanbanTask>, String> {
     info!("Getting all tasks");
+    load_all_tasks()
+}
 
+/// Load all tasks from the database
+pub(crate) fn load_all_tasks() -> Result<Vec<KanbanTask>, String> {
     let conn = init_tasks_db()?;
 
     let mut stmt = conn.prepare(
-        "SELECT id, title, description, status, project_path, branch, worktree_path, plan_id,
-                agent_pid, session_id, base_commit, base_branch, started_at, completed_at, files_changed, diff_summary, created_at, updated_at
-         FROM kanban_tasks ORDER BY created_at DESC"
+        &format!("SELECT {} FROM kanban_tasks ORDER BY created_at DESC", TASK_COLUMNS)
     ).map_err(|e| format!("Failed to prepare query: {}", e))?;
 
-    let tasks_iter = stmt.query_map([], |row| {
-        let status_str: String = row.get(3)?;
-        let files_changed_str: Option<String> = row.get(14)?;
-        let files_changed = files_changed_str.and_then(|s| serde_json::from_str(&s).ok());
-
-        Ok(KanbanTask {
-            id: row.get(0)?,
-            title: row.get(1)?,
-            description: row.get(2)?,
-            status: status_str.parse().unwrap_or(TaskStatus::Backlog),
-            project_path: row.get(4)?,
-            branch: row.get(5)?,
-            worktree_path: row.get(6)?,
-            plan_id: row.get(7)?,
-            agent_pid: row.get(8)?,
-            session_id: row.get(9)?,
-            base_commit: row.get(10)?,
-            base_branch: row.get(11)?,
-            started_at: row.get(12)?,
-            completed_at: row.get(13)?,
-            files_changed,
-            diff_summary: row.get(15)?,
-            created_at: row.get(16)?,
-            updated_at: row.get(17)?,
-        })
-    }).map_err(|e| format!("Failed to query tasks: {}", e))?;
+    let tasks_iter = stmt.query_map([], task_from_row).map_err(|e| format!("Failed to query tasks: {}", e))?;
 
     let mut tasks = Vec::new();
     for task in tasks_iter {
@@ -1105,40 +1313,17 @@ pub async fn get_all_tasks() -> Result<Vec<KanbanTask>, String> {
 #[tauri::command]
 pub async fn get_task(id: String) -> Result<Option<KanbanTask>, String> {
     info!("Getting task: {}", id);
+    load_task(&id)
+}
 
+/// Load a single task by ID
+pub(crate) fn load_task(id: &str) -> Result<Option<KanbanTask>, String> {
     let conn = init_tasks_db()?;
 
     let result = conn.query_row(
-        "SELECT id, title, description, status, project_path, branch, worktree_path, plan_id,
-                agent_pid, session_id, base_commit, base_branch, started_at, completed_at, files_changed, diff_summary, created_at, updated_at
-         FROM kanban_tasks WHERE id = ?1",
+        &format!("SELECT {} FROM kanban_tasks WHERE id = ?1", TASK_COLUMNS),
         params![id],
-        |row| {
-            let status_str: String = row.get(3)?;
-            let files_changed_str: Option<String> = row.get(14)?;
-            let files_changed = files_changed_str.and_then(|s| serde_json::from_str(&s).ok());
-
-            Ok(KanbanTask {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                description: row.get(2)?,
-                status: status_str.parse().unwrap_or(TaskStatus::Backlog),
-                project_path: row.get(4)?,
-                branch: row.get(5)?,
-                worktree_path: row.get(6)?,
-                plan_id: row.get(7)?,
-                agent_pid: row.get(8)?,
-                session_id: row.get(9)?,
-                base_commit: row.get(10)?,
-                base_branch: row.get(11)?,
-                started_at: row.get(12)?,
-                completed_at: row.get(13)?,
-                files_changed,
-                diff_summary: row.get(15)?,
-                created_at: row.get(16)?,
-                updated_at: row.get(17)?,
-            })
-        },
+        task_from_row,
     );
 
     match result {
@@ -1155,6 +1340,11 @@ pub async fn update_task(request: UpdateTaskRequest) -> Result<KanbanTask, Strin
 
     let task_id = request.id.clone();
 
+    if let Some(depends_on) = &request.depends_on {
+        let task = load_task(&task_id)?.ok_or_else(|| format!("Task not found: {}", task_id))?;
+        validate_dependencies(&task_id, &task.project_path, depends_on)?;
+    }
+
     // Do the database update in a block so all non-Send types are dropped before await
     {
         let conn = init_tasks_db()?;
@@ -1168,14 +1358,20 @@ pub async fn update_task(request: UpdateTaskRequest) -> Result<KanbanTask, Strin
                 status = COALESCE(?3, status),
                 branch = COALESCE(?4, branch),
                 plan_id = COALESCE(?5, plan_id),
-                updated_at = ?6
-             WHERE id = ?7",
+                priority = COALESCE(?6, priority),
+                depends_on = COALESCE(?7, depends_on),
+                branch_from_dependency = COALESCE(?8, branch_from_dependency),
+                updated_at = ?9
+             WHERE id = ?10",
             params![
                 request.title,
                 request.description,
                 request.status.map(|s| s.to_string()),
                 request.branch,
                 request.plan_id,
+                request.priority,
+                request.depends_on.as_ref().map(|d| serde_json::to_string(d).unwrap_or_default()),
+                request.branch_from_dependency,
                 now_ts,
                 request.id
             ],
@@ -1186,6 +1382,48 @@ pub async fn update_task(request: UpdateTaskRequest) -> Result<KanbanTask, Strin
     get_task(task_id).await?.ok_or_else(|| "Task not found after update".to_string())
 }
 
+/// Check that `depends_on` names other tasks of the same project and creates no cycle
+fn validate_dependencies(task_id: &str, project_path: &str, depends_on: &[String]) -> Result<(), String> {
+    if depends_on.is_empty() {
+        return Ok(());
+    }
+
+    let tasks = load_all_tasks()?;
+    let by_id: HashMap<&str, &KanbanTask> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+
+    for dependency in depends_on {
+        let prerequisite = by_id.get(dependency.as_str())
+            .ok_or_else(|| format!("Prerequisite task not found: {}", dependency))?;
+        if prerequisite.project_path != project_path {
+            return Err(format!("Task \"{}\" belongs to a different project", prerequisite.title));
+        }
+    }
+
+    let graph: HashMap<String, Vec<String>> = tasks.iter()
+        .map(|t| (t.id.clone(), t.depends_on.clone()))
+        .collect();
+
+    if let Some(cycle) = crate::dependencies::find_cycle(&graph, task_id, depends_on) {
+        let titles: Vec<&str> = cycle.iter()
+            .map(|id| by_id.get(id.as_str()).map(|t| t.title.as_str()).unwrap_or("this task"))
+            .collect();
+        return Err(format!("Dependency cycle: {}", titles.join(" → ")));
+    }
+
+    Ok(())
+}
+
+/// Whether a task's prerequisites let it start, and where its worktree starts from
+fn task_readiness(task: &KanbanTask) -> Result<crate::dependencies::Readiness, String> {
+    if task.depends_on.is_empty() {
+        return Ok(crate::dependencies::Readiness::Ready(None));
+    }
+
+    let tasks = load_all_tasks()?;
+    let by_id: HashMap<&str, &KanbanTask> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    Ok(crate::dependencies::readiness(task, &by_id))
+}
+
 /// Update task status (convenience method for drag-and-drop)
 #[tauri::command]
 pub async fn update_task_status(id: String, status: TaskStatus) -> Result<KanbanTask, String> {
@@ -1229,6 +1467,7 @@ pub async fn update_task_status(id: String, status: TaskStatus) -> Result<Kanban
 pub async fn delete_task(id: String) -> Result<(), String> {
     info!("Deleting task: {}", id);
 
+    let task = load_task(&id)?;
     let conn = init_tasks_db()?;
 
     conn.execute(
@@ -1236,6 +1475,13 @@ pub async fn delete_task(id: String) -> Result<(), String> {
         params![id],
     ).map_err(|e| format!("Failed to delete task: {}", e))?;
 
+    crate::decompose::remove(&id);
+    if let Some(task) = task {
+        let manager = TASK_AGENT_MANAGER.lock()
+            .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
+        discard_attempts(&manager, &task, None)?;
+    }
+
     info!("Task {} deleted", id);
     Ok(())
 }
@@ -1248,37 +1494,10 @@ pub async fn get_tasks_by_project(project_path: String) -> Result<Vec<KanbanTask
     let conn = init_tasks_db()?;
 
     let mut stmt = conn.prepare(
-        "SELECT id, title, description, status, project_path, branch, worktree_path, plan_id,
-                agent_pid, session_id, base_commit, base_branch, started_at, completed_at, files_changed, diff_summary, created_at, updated_at
-         FROM kanban_tasks WHERE project_path = ?1 ORDER BY created_at DESC"
+        &format!("SELECT {} FROM kanban_tasks WHERE project_path = ?1 ORDER BY created_at DESC", TASK_COLUMNS)
     ).map_err(|e| format!("Failed to prepare query: {}", e))?;
 
-    let tasks_iter = stmt.query_map(params![project_path], |row| {
-        let status_str: String = row.get(3)?;
-        let files_changed_str: Option<String> = row.get(14)?;
-        let files_changed = files_changed_str.and_then(|s| serde_json::from_str(&s).ok());
-
-        Ok(KanbanTask {
-            id: row.get(0)?,
-            title: row.get(1)?,
-            description: row.get(2)?,
-            status: status_str.parse().unwrap_or(TaskStatus::Backlog),
-            project_path: row.get(4)?,
-            branch: row.get(5)?,
-            worktree_path: row.get(6)?,
-            plan_id: row.get(7)?,
-            agent_pid: row.get(8)?,
-            session_id: row.get(9)?,
-            base_commit: row.get(10)?,
-            base_branch: row.get(11)?,
-            started_at: row.get(12)?,
-            completed_at: row.get(13)?,
-            files_changed,
-            diff_summary: row.get(15)?,
-            created_at: row.get(16)?,
-            updated_at: row.get(17)?,
-        })
-    }).map_err(|e| format!("Failed to query tasks: {}", e))?;
+    let tasks_iter = stmt.query_map(params![project_path], task_from_row).map_err(|e| format!("Failed to query tasks: {}", e))?;
 
     let mut tasks = Vec::new();
     for task in tasks_iter {
@@ -1301,6 +1520,7 @@ pub async fn start_task_agent(
     app: tauri::AppHandle,
     task_id: String,
     prompt: Option<String>,
+    backend: Option<String>,
 ) -> Result<TaskAgentInfo, String> {
     info!("Starting agent for task: {}", task_id);
 
@@ -1308,14 +1528,58 @@ pub async fn start_task_agent(
     let task = get_task(task_id.clone()).await?
         .ok_or_else(|| format!("Task not found: {}", task_id))?;
 
+    if !crate::attempts::load_attempts(&task_id)?.is_empty() {
+        return Err("Task is running as parallel attempts; merge or discard them first".to_string());
+    }
+
     // Use provided prompt or task description (just the description, not formatted)
     let prompt = prompt.unwrap_or_else(|| task.description.clone());
 
-    // Start the agent
+    // Pick the backend: explicit choice, then the one the task already ran with, then the default
+    let settings = load_settings();
+    let backend_id = backend
+        .or_else(|| task.agent_backend.clone())
+        .unwrap_or_else(|| settings.agent_backend.clone());
+    let agent_backend = crate::agent_backend::backend_for(&backend_id, settings.agent_command.as_deref())
+        .map_err(|e| format!("Failed to start agent: {}", e))?;
+
+    // A task waiting on prerequisites is queued and starts once they're merged
+    let start_point = match task_readiness(&task)? {
+        crate::dependencies::Readiness::Ready(start_point) => start_point,
+        crate::dependencies::Readiness::Blocked(waiting_on) => {
+            info!("Task {} is waiting on {} prerequisite(s)", task_id, waiting_on.len());
+            return enqueue_task(&task, &prompt, &backend_id);
+        }
+    };
+
+    // Start the agent, or queue the task when all agent slots are taken
     let manager = TASK_AGENT_MANAGER.lock()
         .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
 
-    let agent_info = manager.start_agent(&task_id, &task.project_path, &prompt, app)
+    if !manager.has_capacity(&task.project_path, &settings.agent_limits(&task.project_path)) {
+        return enqueue_task(&task, &prompt, &backend_id);
+    }
+
+    launch_task_agent(&manager, &task, &prompt, agent_backend.into(), start_point, app)
+}
+
+/// Start the agent and record it on the task
+fn launch_task_agent(
+    manager: &crate::task_agent::TaskAgentManager,
+    task: &KanbanTask,
+    prompt: &str,
+    backend: std::sync::Arc<dyn crate::agent_backend::AgentBackend>,
+    start_point: Option<crate::task_agent::StartPoint>,
+    app: tauri::AppHandle,
+) -> Result<TaskAgentInfo, String> {
+    let task_id = &task.id;
+
+    // Only a fresh task starts from its dependencies; a resumed one keeps its worktree
+    let options = crate::task_agent::StartOptions {
+        start_point: start_point.filter(|_| task.worktree_path.is_none()),
+        ..Default::default()
+    };
+    let agent_info = manager.start_agent(task_id, &task.project_path, prompt, backend, &options, app)
         .map_err(|e| format!("Failed to start agent: {}", e))?;
 
     // Update task with agent info
@@ -1330,15 +1594,19 @@ pub async fn start_task_agent(
             worktree_path = ?3,
             base_commit = ?4,
             base_branch = ?5,
-            started_at = ?6,
-            updated_at = ?7
-         WHERE id = ?8",
+            agent_backend = ?6,
+            queued_at = NULL,
+            queued_prompt = NULL,
+            started_at = ?7,
+            updated_at = ?8
+         WHERE id = ?9",
         params![
             agent_info.pid,
             agent_info.branch,
             agent_info.worktree_path,
             agent_info.base_commit,
             agent_info.base_branch,
+            agent_info.backend,
             now_ts,
             now_ts,
             task_id
@@ -1349,11 +1617,280 @@ pub async fn start_task_agent(
     Ok(agent_info)
 }
 
+/// Put a task in the run queue; it starts when an agent slot frees up
+fn enqueue_task(task: &KanbanTask, prompt: &str, backend_id: &str) -> Result<TaskAgentInfo, String> {
+    let conn = init_tasks_db()?;
+    let now_ts = now();
+
+    conn.execute(
+        "UPDATE kanban_tasks SET
+            status = 'Queued',
+            queued_at = ?1,
+            queued_prompt = ?2,
+            agent_backend = ?3,
+            updated_at = ?4
+         WHERE id = ?5",
+        params![now_ts, prompt, backend_id, now_ts, task.id],
+    ).map_err(|e| format!("Failed to queue task: {}", e))?;
+
+    info!("All agent slots are busy, queued task {}", task.id);
+
+    Ok(TaskAgentInfo {
+        task_id: task.id.clone(),
+        pid: 0,
+        status: crate::task_agent::TaskAgentStatus::Queued,
+        backend: backend_id.to_string(),
+        project_path: task.project_path.clone(),
+        worktree_path: task.worktree_path.clone().unwrap_or_default(),
+        branch: task.branch.clone().unwrap_or_default(),
+        base_commit: task.base_commit.clone().unwrap_or_default(),
+        base_branch: task.base_branch.clone().unwrap_or_default(),
+        started_at: 0,
+        output_lines: Vec::new(),
+    })
+}
+
+/// Take a task out of the run queue. A task that ran before goes back to paused.
+fn dequeue_task(task: &KanbanTask) -> Result<(), String> {
+    let status = if task.started_at.is_some() { TaskStatus::InProgress } else { TaskStatus::Backlog };
+    let conn = init_tasks_db()?;
+
+    conn.execute(
+        "UPDATE kanban_tasks SET status = ?1, queued_at = NULL, queued_prompt = NULL, updated_at = ?2 WHERE id = ?3",
+        params![status.to_string(), now(), task.id],
+    ).map_err(|e| format!("Failed to dequeue task: {}", e))?;
+
+    Ok(())
+}
+
+/// Start queued tasks while agent slots are free (called on startup, after settings
+/// change, whenever an agent exits and when a prerequisite is reviewed or merged).
+/// The queue lives in the database, so it survives restarts.
+pub fn drain_agent_queue(app: tauri::AppHandle) {
+    let (queue, tasks) = match load_agent_queue().and_then(|queue| Ok((queue, load_all_tasks()?))) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            tracing::warn!("Failed to load the agent queue: {}", e);
+            return;
+        }
+    };
+    if queue.is_empty() {
+        return;
+    }
+
+    // Tasks still waiting on prerequisites stay queued
+    let by_id: HashMap<&str, &KanbanTask> = tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mut start_points = HashMap::new();
+    let queue: Vec<_> = queue.into_iter()
+        .filter(|run| match by_id.get(run.task_id.as_str()).map(|t| crate::dependencies::readiness(t, &by_id)) {
+            Some(crate::dependencies::Readiness::Ready(start_point)) => {
+                start_points.insert(run.task_id.clone(), start_point);
+                true
+            }
+            _ => false,
+        })
+        .collect();
+
+    let settings = load_settings();
+    let manager = match TASK_AGENT_MANAGER.lock() {
+        Ok(manager) => manager,
+        Err(e) => {
+            tracing::warn!("Failed to lock agent manager: {}", e);
+            return;
+        }
+    };
+
+    for run in manager.schedule(&queue, |project| settings.agent_limits(project)) {
+        let result = load_task(&run.task_id).and_then(|task| {
+            let task = task.ok_or_else(|| format!("Task not found: {}", run.task_id))?;
+            let backend_id = task.agent_backend.clone().unwrap_or_else(|| settings.agent_backend.clone());
+            let backend = crate::agent_backend::backend_for(&backend_id, settings.agent_command.as_deref())
+                .map_err(|e| format!("Failed to start agent: {}", e))?;
+            let start_point = start_points.remove(&run.task_id).flatten();
+            launch_task_agent(&manager, &task, &run.prompt, backend.into(), start_point, app.clone())
+        });
+
+        if let Err(e) = result {
+            // Take the task out of the queue so it doesn't block the slot forever
+            tracing::warn!("Failed to start queued task {}: {}", run.task_id, e);
+            if let Ok(Some(task)) = load_task(&run.task_id) {
+                let _ = dequeue_task(&task);
+            }
+            let _ = app.emit("agent-status", crate::task_agent::AgentStatusEvent {
+                task_id: run.task_id.clone(),
+                status: crate::task_agent::TaskAgentStatus::Failed,
+                message: Some(e),
+            });
+        }
+    }
+}
+
+/// Tasks waiting in the run queue
+fn load_agent_queue() -> Result<Vec<crate::task_agent::QueuedRun>, String> {
+    let conn = init_tasks_db()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, project_path, priority, queued_at, queued_prompt FROM kanban_tasks WHERE status = 'Queued'"
+    ).map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let queue = stmt.query_map([], |row| {
+        Ok(crate::task_agent::QueuedRun {
+            task_id: row.get(0)?,
+            project_path: row.get(1)?,
+            priority: row.get(2)?,
+            queued_at: row.get::<_, Option<u64>>(3)?.unwrap_or_default(),
+            prompt: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+        })
+    })
+        .map_err(|e| format!("Failed to query tasks: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(queue)
+}
+
+/// Re-attach to agents left running by a previous app instance (called on startup).
+/// Tasks whose agent died while the app was closed are paused (agent_pid cleared).
+pub fn reattach_agents(app: tauri::AppHandle) {
+    let tasks = match load_tasks_with_agents() {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            tracing::warn!("Failed to load tasks for agent re-attach: {}", e);
+            return;
+        }
+    };
+
+    let settings = load_settings();
+
+    for task in tasks {
+        let backend_id = task.agent_backend.clone().unwrap_or_else(|| settings.agent_backend.clone());
+        let info = TaskAgentInfo {
+            task_id: task.id.clone(),
+            pid: task.agent_pid.unwrap_or_default(),
+            status: crate::task_agent::TaskAgentStatus::Starting,
+            backend: backend_id,
+            project_path: task.project_path.clone(),
+            worktree_path: task.worktree_path.clone().unwrap_or_default(),
+            branch: task.branch.clone().unwrap_or_default(),
+            base_commit: task.base_commit.clone().unwrap_or_default(),
+            base_branch: task.base_branch.clone().unwrap_or_default(),
+            started_at: task.started_at.unwrap_or_default() as i64,
+            output_lines: Vec::new(),
+        };
+
+        // Agent died while the app was closed - pause the task
+        if !reattach_agent(info, &settings, &app) {
+            if let Err(e) = clear_agent_pid(&task.id) {
+                tracing::warn!("Failed to pause task {}: {}", task.id, e);
+            }
+        }
+    }
+
+    let attempts = crate::attempts::load_all_attempts().unwrap_or_default();
+    for attempt in attempts.into_iter().filter(|a| a.agent_pid.is_some()) {
+        let project_path = match load_task(&attempt.task_id) {
+            Ok(Some(task)) => task.project_path,
+            _ => continue,
+        };
+        let info = TaskAgentInfo {
+            task_id: attempt.id.clone(),
+            pid: attempt.agent_pid.unwrap_or_default(),
+            status: crate::task_agent::TaskAgentStatus::Starting,
+            backend: attempt.backend,
+            project_path,
+            worktree_path: attempt.worktree_path,
+            branch: attempt.branch,
+            base_commit: attempt.base_commit,
+            base_branch: attempt.base_branch,
+            started_at: attempt.started_at as i64,
+            output_lines: Vec::new(),
+        };
+
+        if !reattach_agent(info, &settings, &app) {
+            let _ = crate::attempts::clear_attempt_pid(&attempt.id);
+        }
+    }
+}
+
+/// Re-attach one agent; returns whether it is still running
+fn reattach_agent(info: TaskAgentInfo, settings: &MedusaSettings, app: &tauri::AppHandle) -> bool {
+    let agent_id = info.task_id.clone();
+    let backend = match crate::agent_backend::backend_for(&info.backend, settings.agent_command.as_deref()) {
+        Ok(backend) => backend,
+        Err(e) => {
+            tracing::warn!("Cannot re-attach agent {}: {}", agent_id, e);
+            return true;
+        }
+    };
+
+    let status = match TASK_AGENT_MANAGER.lock() {
+        Ok(manager) => manager.reattach_agent(info, backend.into(), app.clone()),
+        Err(e) => {
+            tracing::warn!("Failed to lock agent manager: {}", e);
+            return true;
+        }
+    };
+
+    match status {
+        Ok(crate::task_agent::TaskAgentStatus::Running) => true,
+        Ok(_) => false,
+        Err(e) => {
+            tracing::warn!("Failed to re-attach agent {}: {}", agent_id, e);
+            true
+        }
+    }
+}
+
+/// Tasks that had a live agent when last seen
+fn load_tasks_with_agents() -> Result<Vec<KanbanTask>, String> {
+    let conn = init_tasks_db()?;
+
+    let mut stmt = conn.prepare(
+        &format!("SELECT {} FROM kanban_tasks WHERE status = 'InProgress' AND agent_pid IS NOT NULL", TASK_COLUMNS)
+    ).map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let tasks = stmt.query_map([], task_from_row)
+        .map_err(|e| format!("Failed to query tasks: {}", e))?
+        .filter_map(|t| t.ok())
+        .collect();
+
+    Ok(tasks)
+}
+
+/// Clear a task's agent_pid (the paused state: InProgress without a running agent)
+pub(crate) fn clear_agent_pid(task_id: &str) -> Result<(), String> {
+    let conn = init_tasks_db()?;
+    conn.execute(
+        "UPDATE kanban_tasks SET agent_pid = NULL, updated_at = ?1 WHERE id = ?2",
+        params![now(), task_id],
+    ).map_err(|e| format!("Failed to update task: {}", e))?;
+    Ok(())
+}
+
+/// Point a task at a (re)created worktree
+pub(crate) fn set_task_worktree_path(task_id: &str, worktree_path: &str) -> Result<(), String> {
+    let conn = init_tasks_db()?;
+    conn.execute(
+        "UPDATE kanban_tasks SET worktree_path = ?1, updated_at = ?2 WHERE id = ?3",
+        params![worktree_path, now(), task_id],
+    ).map_err(|e| format!("Failed to update task: {}", e))?;
+    Ok(())
+}
+
 /// Stop an agent for a task (pauses the agent, keeps task in InProgress)
 #[tauri::command]
 pub async fn stop_task_agent(task_id: String) -> Result<(), String> {
     info!("Pausing agent for task: {}", task_id);
 
+    // A queued task has no agent yet - just leave the queue
+    if let Some(task) = load_task(&task_id)? {
+        if task.status == TaskStatus::Queued {
+            dequeue_task(&task)?;
+            info!("Removed task {} from the agent queue", task_id);
+            return Ok(());
+        }
+    }
+
     let manager = TASK_AGENT_MANAGER.lock()
         .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
 
@@ -1497,6 +2034,291 @@ pub async fn get_task_file_diff(task_id: String, file_path: String) -> Result<St
         .map_err(|e| format!("Failed to get diff: {}", e))
 }
 
+// ============== Task Attempts ==============
+
+use crate::attempts::{AttemptComparison, TaskAttempt};
+
+/// Start several agents on the same task at once, each in its own worktree and branch.
+/// Each launch profile is one attempt; attempts may differ in backend, model or arguments.
+#[tauri::command]
+pub async fn start_task_attempts(
+    app: tauri::AppHandle,
+    task_id: String,
+    profiles: Vec<crate::agent_backend::LaunchProfile>,
+    prompt: Option<String>,
+) -> Result<Vec<TaskAttempt>, String> {
+    use crate::attempts::{attempt_agent_id, attempt_label, MAX_ATTEMPTS};
+
+    info!("Starting {} attempts for task {}", profiles.len(), task_id);
+
+    if profiles.len() < 2 || profiles.len() > MAX_ATTEMPTS {
+        return Err(format!("Start between 2 and {} attempts", MAX_ATTEMPTS));
+    }
+
+    let task = load_task(&task_id)?
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    if task.status != TaskStatus::Backlog {
+        return Err("Only Backlog tasks can be started as parallel attempts".to_string());
+    }
+
+    let start_point = match task_readiness(&task)? {
+        crate::dependencies::Readiness::Ready(start_point) => start_point,
+        crate::dependencies::Readiness::Blocked(_) => {
+            return Err("Task is waiting on its prerequisites".to_string());
+        }
+    };
+
+    let prompt = prompt.unwrap_or_else(|| task.description.clone());
+
+    // Resolve every backend up front so a bad profile doesn't leave half the attempts running
+    let settings = load_settings();
+    let backends = profiles
+        .iter()
+        .map(|profile| {
+            let backend_id = profile.backend.clone()
+                .or_else(|| task.agent_backend.clone())
+                .unwrap_or_else(|| settings.agent_backend.clone());
+            crate::agent_backend::backend_for(&backend_id, settings.agent_command.as_deref())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to start attempts: {}", e))?;
+
+    let manager = TASK_AGENT_MANAGER.lock()
+        .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
+
+    let limits = settings.agent_limits(&task.project_path);
+    if manager.free_slots(&task.project_path, &limits) < profiles.len() {
+        return Err(format!(
+            "Not enough free agent slots for {} attempts. Wait for running agents or raise the limits in Settings.",
+            profiles.len()
+        ));
+    }
+
+    let mut attempts: Vec<TaskAttempt> = Vec::new();
+    for (index, (profile, backend)) in profiles.into_iter().zip(backends).enumerate() {
+        let label = attempt_label(index);
+        let agent_id = attempt_agent_id(&task_id, &label);
+        let options = crate::task_agent::StartOptions {
+            start_point: start_point.clone(),
+            profile: profile.clone(),
+        };
+
+        let agent_info = match manager.start_agent(&agent_id, &task.project_path, &prompt, backend.into(), &options, app.clone()) {
+            Ok(agent_info) => agent_info,
+            Err(e) => {
+                // Don't leave a partial race behind
+                for attempt in &attempts {
+                    discard_attempt(&manager, &task.project_path, attempt);
+                }
+                return Err(format!("Failed to start attempt {}: {}", label, e));
+            }
+        };
+
+        let attempt = TaskAttempt {
+            id: agent_id,
+            task_id: task_id.clone(),
+            label,
+            profile,
+            backend: agent_info.backend,
+            branch: agent_info.branch,
+            worktree_path: agent_info.worktree_path,
+            base_commit: agent_info.base_commit,
+            base_branch: agent_info.base_branch,
+            agent_pid: Some(agent_info.pid),
+            started_at: now(),
+        };
+        crate::attempts::insert_attempt(&attempt)?;
+        attempts.push(attempt);
+    }
+
+    // The task's work lives in its attempts until one of them is merged
+    let conn = init_tasks_db()?;
+    let now_ts = now();
+    conn.execute(
+        "UPDATE kanban_tasks SET status = 'InProgress', started_at = ?1, updated_at = ?2 WHERE id = ?3",
+        params![now_ts, now_ts, task_id],
+    ).map_err(|e| format!("Failed to update task: {}", e))?;
+
+    info!("Started {} attempts for task {}", attempts.len(), task_id);
+    Ok(attempts)
+}
+
+/// Get a task's attempts
+#[tauri::command]
+pub async fn get_task_attempts(task_id: String) -> Result<Vec<TaskAttempt>, String> {
+    crate::attempts::load_attempts(&task_id)
+}
+
+/// Compare a task's attempts side by side: agent status, size of the changes and cost
+#[tauri::command]
+pub async fn compare_task_attempts(task_id: String) -> Result<Vec<AttemptComparison>, String> {
+    use crate::git::GitManager;
+
+    let task = load_task(&task_id)?
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    let git = GitManager::new(task.project_path.clone())
+        .map_err(|e| format!("Failed to open git repo: {}", e))?;
+    let settings = load_settings();
+
+    let manager = TASK_AGENT_MANAGER.lock()
+        .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
+
+    let comparisons = crate::attempts::load_attempts(&task_id)?
+        .into_iter()
+        .map(|attempt| {
+            let cost_usd = crate::agent_backend::backend_for(&attempt.backend, settings.agent_command.as_deref())
+                .ok()
+                .and_then(|backend| backend.total_cost(&manager.get_agent_output(&attempt.id).unwrap_or_default()));
+
+            AttemptComparison {
+                status: manager.get_agent(&attempt.id).map(|a| a.status),
+                diff: git.get_worktree_diff_stats(&attempt.id, &attempt.base_commit).unwrap_or_default(),
+                changed_files: git.get_worktree_all_changes_vs_base(&attempt.id, Some(&attempt.base_commit))
+                    .unwrap_or_default(),
+                cost_usd,
+                attempt,
+            }
+        })
+        .collect();
+
+    Ok(comparisons)
+}
+
+/// Stop an attempt's agent (its worktree is kept for comparison)
+#[tauri::command]
+pub async fn stop_task_attempt(attempt_id: String) -> Result<(), String> {
+    info!("Stopping attempt {}", attempt_id);
+
+    let manager = TASK_AGENT_MANAGER.lock()
+        .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
+
+    manager.stop_agent(&attempt_id)
+        .map_err(|e| format!("Failed to stop agent: {}", e))?;
+
+    crate::attempts::clear_attempt_pid(&attempt_id)
+}
+
+/// Discard one attempt. A task with no attempts left goes back to the backlog.
+#[tauri::command]
+pub async fn discard_task_attempt(task_id: String, attempt_id: String) -> Result<(), String> {
+    info!("Discarding attempt {}", attempt_id);
+
+    let task = load_task(&task_id)?
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+    let attempts = crate::attempts::load_attempts(&task_id)?;
+    let attempt = attempts.iter()
+        .find(|a| a.id == attempt_id)
+        .ok_or_else(|| format!("Attempt not found: {}", attempt_id))?;
+
+    {
+        let manager = TASK_AGENT_MANAGER.lock()
+            .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
+        discard_attempt(&manager, &task.project_path, attempt);
+    }
+
+    if attempts.len() == 1 {
+        let conn = init_tasks_db()?;
+        conn.execute(
+            "UPDATE kanban_tasks SET status = 'Backlog', started_at = NULL, updated_at = ?1 WHERE id = ?2",
+            params![now(), task_id],
+        ).map_err(|e| format!("Failed to update task: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Merge one attempt: the other attempts are discarded, the chosen one becomes the
+/// task's worktree and branch, and the task is committed and merged as usual.
+#[tauri::command]
+pub async fn merge_task_attempt(app: tauri::AppHandle, task_id: String, attempt_id: String) -> Result<(), String> {
+    use crate::git::GitManager;
+
+    info!("Merging attempt {} of task {}", attempt_id, task_id);
+
+    let task = load_task(&task_id)?
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+    let attempt = crate::attempts::load_attempts(&task_id)?
+        .into_iter()
+        .find(|a| a.id == attempt_id)
+        .ok_or_else(|| format!("Attempt not found: {}", attempt_id))?;
+
+    let git = GitManager::new(task.project_path.clone())
+        .map_err(|e| format!("Failed to open git repo: {}", e))?;
+
+    {
+        let manager = TASK_AGENT_MANAGER.lock()
+            .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
+
+        discard_attempts(&manager, &task, Some(&attempt.id))?;
+
+        // Promote the chosen attempt to the task's own worktree, branch and session
+        let _ = manager.stop_agent(&attempt.id);
+        manager.forget_agent(&attempt.id);
+    }
+
+    let worktree_path = git.move_worktree(&attempt.id, &task_id)
+        .map_err(|e| format!("Failed to adopt attempt: {}", e))?;
+    let branch = crate::task_agent::task_branch_name(&task_id);
+    git.rename_branch(&attempt.branch, &branch)
+        .map_err(|e| format!("Failed to adopt attempt: {}", e))?;
+    crate::task_agent::move_session_files(&attempt.id, &task_id);
+    crate::attempts::delete_attempt(&attempt.id)?;
+
+    let conn = init_tasks_db()?;
+    conn.execute(
+        "UPDATE kanban_tasks SET
+            agent_pid = NULL,
+            branch = ?1,
+            worktree_path = ?2,
+            base_commit = ?3,
+            base_branch = ?4,
+            agent_backend = ?5,
+            updated_at = ?6
+         WHERE id = ?7",
+        params![
+            branch,
+            worktree_path.to_string_lossy(),
+            attempt.base_commit,
+            attempt.base_branch,
+            attempt.backend,
+            now(),
+            task_id
+        ],
+    ).map_err(|e| format!("Failed to update task: {}", e))?;
+
+    // Commit what the agent left uncommitted, then merge like any reviewed task
+    send_task_to_review(app.clone(), task_id.clone()).await?;
+    merge_task(app, task_id).await
+}
+
+/// Stop an attempt's agent and remove its worktree, branch, session and record
+fn discard_attempt(manager: &crate::task_agent::TaskAgentManager, project_path: &str, attempt: &TaskAttempt) {
+    if let Err(e) = manager.cleanup_agent(&attempt.id, project_path) {
+        tracing::warn!("Failed to clean up attempt {}: {}", attempt.id, e);
+    }
+    if let Ok(git) = crate::git::GitManager::new(project_path.to_string()) {
+        let _ = git.delete_branch(&attempt.branch); // Ignore error if branch doesn't exist
+    }
+    crate::task_agent::remove_session_files(&attempt.id);
+    let _ = crate::attempts::delete_attempt(&attempt.id);
+}
+
+/// Discard all of a task's attempts except `keep`
+fn discard_attempts(
+    manager: &crate::task_agent::TaskAgentManager,
+    task: &KanbanTask,
+    keep: Option<&str>,
+) -> Result<(), String> {
+    for attempt in crate::attempts::load_attempts(&task.id)? {
+        if keep != Some(attempt.id.as_str()) {
+            discard_attempt(manager, &task.project_path, &attempt);
+        }
+    }
+    Ok(())
+}
+
 // ============== Task Plan Review ==============
 
 /// Task plan pending review (from agent entering plan mode)
@@ -1521,6 +2343,11 @@ fn get_task_plans_dir() -> PathBuf {
 /// Get pending plan for a task (if agent entered plan mode)
 #[tauri::command]
 pub async fn get_task_plan(task_id: String) -> Result<Option<TaskPlan>, String> {
+    load_task_plan(&task_id)
+}
+
+/// Load the pending plan for a task, if its agent is waiting for plan approval
+pub(crate) fn load_task_plan(task_id: &str) -> Result<Option<TaskPlan>, String> {
     let plan_file = get_task_plans_dir().join(format!("{}.json", task_id));
 
     if !plan_file.exists() {
@@ -1541,7 +2368,7 @@ pub async fn get_task_plan(task_id: String) -> Result<Option<TaskPlan>, String>
         .map_err(|e| format!("Failed to read plan content: {}", e))?;
 
     Ok(Some(TaskPlan {
-        task_id: task_id.clone(),
+        task_id: task_id.to_string(),
         plan_file: plan_path.to_string(),
         response_file: pending.get("response_file")
             .and_then(|v| v.as_str())
@@ -1561,6 +2388,11 @@ pub async fn get_task_plan(task_id: String) -> Result<Option<TaskPlan>, String>
 /// Respond to a task's plan (approve or reject)
 #[tauri::command]
 pub async fn respond_to_task_plan(task_id: String, approved: bool, feedback: Option<String>) -> Result<(), String> {
+    write_task_plan_response(&task_id, approved, feedback)
+}
+
+/// Answer the plan-mode hook an agent is waiting on, and clear the pending plan
+pub(crate) fn write_task_plan_response(task_id: &str, approved: bool, feedback: Option<String>) -> Result<(), String> {
     info!("Responding to task plan {}: approved={}", task_id, approved);
 
     let plan_file = get_task_plans_dir().join(format!("{}.json", task_id));
@@ -1597,9 +2429,120 @@ pub async fn respond_to_task_plan(task_id: String, approved: bool, feedback: Opt
     Ok(())
 }
 
+// ============== Task Decomposition ==============
+
+/// Ask the task's agent (in plan-only mode) to split a Backlog task into subtasks.
+/// Runs in the background; the result arrives as a `task-decomposition` event.
+#[tauri::command]
+pub async fn decompose_task(app: tauri::AppHandle, task_id: String) -> Result<(), String> {
+    info!("Decomposing task: {}", task_id);
+
+    let task = load_task(&task_id)?
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    if task.status != TaskStatus::Backlog {
+        return Err("Only Backlog tasks can be split into subtasks".to_string());
+    }
+    if matches!(crate::decompose::load(&task_id), Some(d) if d.status == crate::decompose::DecompositionStatus::Running) {
+        return Err("This task is already being decomposed".to_string());
+    }
+
+    let settings = load_settings();
+    let backend_id = task.agent_backend.clone().unwrap_or_else(|| settings.agent_backend.clone());
+    let backend = crate::agent_backend::backend_for(&backend_id, settings.agent_command.as_deref())
+        .map_err(|e| format!("Failed to decompose task: {}", e))?;
+    backend.check_installed()
+        .map_err(|e| format!("Failed to decompose task: {}", e))?;
+
+    let prompt = crate::decompose::build_prompt(&task.title, &task.description);
+    crate::decompose::start(&task_id, &task.project_path, prompt, backend.into(), app);
+    Ok(())
+}
+
+/// Get the proposed breakdown for a task (if one was requested)
+#[tauri::command]
+pub async fn get_task_decomposition(task_id: String) -> Result<Option<crate::decompose::TaskDecomposition>, String> {
+    Ok(crate::decompose::load(&task_id))
+}
+
+/// Create the (reviewed) subtasks of a task. `depends_on` in each subtask refers to
+/// other entries of `subtasks` by index.
+#[tauri::command]
+pub async fn create_subtasks(
+    parent_id: String,
+    subtasks: Vec<crate::decompose::SubtaskProposal>,
+) -> Result<Vec<KanbanTask>, String> {
+    info!("Creating {} subtask(s) of task {}", subtasks.len(), parent_id);
+
+    let parent = load_task(&parent_id)?
+        .ok_or_else(|| format!("Task not found: {}", parent_id))?;
+
+    if subtasks.iter().any(|s| s.title.trim().is_empty()) {
+        return Err("Every subtask needs a title".to_string());
+    }
+
+    let ids: Vec<String> = subtasks.iter().map(|_| Uuid::new_v4().to_string()).collect();
+    let mut graph: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (index, subtask) in subtasks.iter().enumerate() {
+        let depends_on = subtask.depends_on.iter()
+            .map(|&dep| ids.get(dep).cloned().ok_or_else(|| format!("Subtask \"{}\" depends on a missing subtask", subtask.title)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if crate::dependencies::find_cycle(&graph, &ids[index], &depends_on).is_some() {
+            return Err(format!("Subtask \"{}\" is part of a dependency cycle", subtask.title));
+        }
+        graph.insert(ids[index].clone(), depends_on);
+    }
+
+    // Insert all subtasks in one transaction so a failure leaves nothing behind
+    let mut conn = init_tasks_db()?;
+    let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let now_ts = now();
+    let mut tasks = Vec::new();
+
+    for (index, subtask) in subtasks.into_iter().enumerate() {
+        let task = KanbanTask {
+            id: ids[index].clone(),
+            title: subtask.title.trim().to_string(),
+            description: subtask.description,
+            status: TaskStatus::Backlog,
+            project_path: parent.project_path.clone(),
+            branch: None,
+            worktree_path: None,
+            plan_id: None,
+            agent_pid: None,
+            session_id: None,
+            agent_backend: parent.agent_backend.clone(),
+            base_commit: None,
+            base_branch: None,
+            priority: parent.priority,
+            queued_at: None,
+            depends_on: graph.remove(&ids[index]).unwrap_or_default(),
+            branch_from_dependency: parent.branch_from_dependency,
+            parent_id: Some(parent.id.clone()),
+            verification_status: None,
+            started_at: None,
+            completed_at: None,
+            files_changed: None,
+            diff_summary: None,
+            created_at: now_ts,
+            updated_at: now_ts,
+        };
+        insert_task(&tx, &task)?;
+        tasks.push(task);
+    }
+
+    tx.commit().map_err(|e| format!("Failed to create subtasks: {}", e))?;
+    crate::decompose::remove(&parent_id);
+
+    info!("Created {} subtask(s) of task {}", tasks.len(), parent_id);
+    Ok(tasks)
+}
+
 /// Merge a task's worktree branch into main and mark as done
 #[tauri::command]
-pub async fn merge_task(task_id: String) -> Result<(), String> {
+pub async fn merge_task(app: tauri::AppHandle, task_id: String) -> Result<(), String> {
     use crate::git::GitManager;
     use std::process::Command;
 
@@ -1687,6 +2630,9 @@ pub async fn merge_task(task_id: String) -> Result<(), String> {
     ).map_err(|e| format!("Failed to update task: {}", e))?;
 
     info!("Task {} merged successfully with {} files changed", task_id, files_changed.len());
+
+    // Dependent tasks may be startable now
+    std::thread::spawn(move || drain_agent_queue(app));
     Ok(())
 }
 
@@ -1705,6 +2651,7 @@ pub async fn reject_task(task_id: String) -> Result<(), String> {
         let manager = TASK_AGENT_MANAGER.lock()
             .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
         let _ = manager.stop_agent(&task_id);
+        discard_attempts(&manager, &task, None)?;
     }
 
     // Remove worktree if exists
@@ -1736,18 +2683,17 @@ pub async fn reject_task(task_id: String) -> Result<(), String> {
             completed_at = NULL,
             files_changed = NULL,
             diff_summary = NULL,
+            verification_status = NULL,
+            verification_result = NULL,
+            verification_retries = 0,
             updated_at = ?1
          WHERE id = ?2",
         params![now_ts, task_id],
     ).map_err(|e| format!("Failed to update task: {}", e))?;
 
     // Also clean up session files
-    let sessions_dir = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".medusa")
-        .join("sessions");
-    let _ = std::fs::remove_file(sessions_dir.join(format!("{}.jsonl", task_id)));
-    let _ = std::fs::remove_file(sessions_dir.join(format!("{}.session_id", task_id)));
+    crate::task_agent::remove_session_files(&task_id);
+    crate::supervisor::remove_files(&task_id);
 
     info!("Task {} rejected and moved back to backlog", task_id);
     Ok(())
@@ -1765,7 +2711,7 @@ pub struct TaskCommit {
 
 /// Send task to review - auto-commit uncommitted changes using Claude Code
 #[tauri::command]
-pub async fn send_task_to_review(task_id: String) -> Result<(), String> {
+pub async fn send_task_to_review(app: tauri::AppHandle, task_id: String) -> Result<(), String> {
     use std::process::Command;
     use std::sync::Mutex;
     use once_cell::sync::Lazy;
@@ -1845,19 +2791,159 @@ pub async fn send_task_to_review(task_id: String) -> Result<(), String> {
         }
     }
 
+    // Run the project's verification checks on the committed work
+    let project = load_settings().project(&task.project_path);
+    let verification = if project.verify_commands.is_empty() {
+        None
+    } else {
+        info!("Verifying task {} with {} check(s)", task_id, project.verify_commands.len());
+        let _ = app.emit("task-verification", VerificationEvent { task_id: task_id.clone(), status: None });
+
+        let result = crate::verification::run_checks(
+            std::path::Path::new(worktree_path),
+            &project.verify_commands,
+            std::time::Duration::from_secs(project.verify_timeout_secs),
+        );
+        let _ = app.emit("task-verification", VerificationEvent { task_id: task_id.clone(), status: Some(result.status) });
+        Some(result)
+    };
+    save_verification(&task_id, verification.as_ref())?;
+
+    // Failed checks go back to the agent while the project allows fix retries
+    if let Some(result) = verification.as_ref().filter(|r| r.status == VerificationStatus::Failed) {
+        let retry = load_verification_retries(&task_id)? + 1;
+        if retry <= project.verify_fix_retries {
+            request_verification_fix(&app, &task, result, retry).await?;
+            return Err(format!(
+                "Verification failed; the agent was asked to fix it (retry {} of {})",
+                retry, project.verify_fix_retries
+            ));
+        }
+    }
+
     // Update task status to Review
     let conn = init_tasks_db()?;
     let now_ts = now();
 
     conn.execute(
-        "UPDATE kanban_tasks SET status = 'Review', updated_at = ?1 WHERE id = ?2",
+        "UPDATE kanban_tasks SET status = 'Review', verification_retries = 0, updated_at = ?1 WHERE id = ?2",
         params![now_ts, task_id],
     ).map_err(|e| format!("Failed to update task status: {}", e))?;
 
     info!("Task {} sent to review", task_id);
+
+    // Dependent tasks that branch from this one may be startable now
+    std::thread::spawn(move || drain_agent_queue(app));
     Ok(())
 }
 
+/// Hand failed checks to the task's agent, resuming it if needed, and send the
+/// task to review again once the agent's turn ends
+async fn request_verification_fix(
+    app: &tauri::AppHandle,
+    task: &KanbanTask,
+    result: &VerificationResult,
+    retry: u32,
+) -> Result<(), String> {
+    let task_id = task.id.clone();
+    let prompt = crate::verification::fix_prompt(result);
+    info!("Asking the agent to fix failed verification of task {} (retry {})", task_id, retry);
+
+    let has_session = TASK_AGENT_MANAGER.lock()
+        .map_err(|e| format!("Failed to lock agent manager: {}", e))?
+        .has_active_session(&task_id);
+
+    // A resumed session ignores the start prompt, so the fix request is sent as a message
+    let mut send_prompt = true;
+    if !has_session {
+        let resumes = crate::task_agent::load_session_id(&task_id).is_some();
+        let agent_info = start_task_agent(app.clone(), task_id.clone(), Some(prompt.clone()), None).await?;
+        if agent_info.status == crate::task_agent::TaskAgentStatus::Queued {
+            // The agent starts with the fix request once a slot frees up; re-verify by hand
+            set_verification_retries(&task_id, retry)?;
+            return Ok(());
+        }
+        send_prompt = resumes;
+    }
+
+    let since = {
+        let manager = TASK_AGENT_MANAGER.lock()
+            .map_err(|e| format!("Failed to lock agent manager: {}", e))?;
+        let since = manager.output_len(&task_id);
+        if send_prompt {
+            manager.send_message(&task_id, &prompt)
+                .map_err(|e| format!("Failed to send message: {}", e))?;
+        }
+        since
+    };
+    set_verification_retries(&task_id, retry)?;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if !crate::verification::wait_for_fix(&task_id, since) {
+            info!("Agent for task {} is still fixing verification, leaving it be", task_id);
+            return;
+        }
+
+        // Only carry on if nobody moved the task in the meantime
+        match load_task(&task_id) {
+            Ok(Some(task)) if task.status == TaskStatus::InProgress => {}
+            _ => return,
+        }
+
+        if let Err(e) = tauri::async_runtime::block_on(send_task_to_review(app, task_id.clone())) {
+            info!("Task {} not sent to review after fix: {}", task_id, e);
+        }
+    });
+
+    Ok(())
+}
+
+/// Store a task's latest verification result (None: the project has no checks)
+fn save_verification(task_id: &str, result: Option<&VerificationResult>) -> Result<(), String> {
+    let conn = init_tasks_db()?;
+    conn.execute(
+        "UPDATE kanban_tasks SET verification_status = ?1, verification_result = ?2 WHERE id = ?3",
+        params![
+            result.map(|r| r.status.as_str()),
+            result.and_then(|r| serde_json::to_string(r).ok()),
+            task_id
+        ],
+    ).map_err(|e| format!("Failed to save verification: {}", e))?;
+    Ok(())
+}
+
+fn load_verification_retries(task_id: &str) -> Result<u32, String> {
+    let conn = init_tasks_db()?;
+    conn.query_row(
+        "SELECT verification_retries FROM kanban_tasks WHERE id = ?1",
+        params![task_id],
+        |row| row.get(0),
+    ).map_err(|e| format!("Failed to load verification retries: {}", e))
+}
+
+fn set_verification_retries(task_id: &str, retries: u32) -> Result<(), String> {
+    let conn = init_tasks_db()?;
+    conn.execute(
+        "UPDATE kanban_tasks SET verification_retries = ?1 WHERE id = ?2",
+        params![retries, task_id],
+    ).map_err(|e| format!("Failed to save verification retries: {}", e))?;
+    Ok(())
+}
+
+/// Get the checks and logs of a task's latest verification
+#[tauri::command]
+pub async fn get_task_verification(task_id: String) -> Result<Option<VerificationResult>, String> {
+    let conn = init_tasks_db()?;
+    let result: Option<String> = conn.query_row(
+        "SELECT verification_result FROM kanban_tasks WHERE id = ?1",
+        params![task_id],
+        |row| row.get(0),
+    ).map_err(|e| format!("Failed to load verification: {}", e))?;
+
+    Ok(result.and_then(|r| serde_json::from_str(&r).ok()))
+}
+
 /// Get commits for a task branch (compared to main)
 #[tauri::command]
 pub async fn get_task_commits(task_id: String) -> Result<Vec<TaskCommit>, String> {
@@ -0,0 +1,397 @@
+//! Task Decomposition - Splitting an epic into subtasks
+//!
+//! Runs the task's agent in plan-only mode against the project and asks it for a
+//! breakdown. If the agent submits its answer as a plan (ExitPlanMode), the plan
+//! review hook hands it to us through the task plan files and we decline the plan
+//! so the agent stops without touching the code. The breakdown is parsed into
+//! subtask proposals that the reviewer edits before creating them as child tasks.
+
+use crate::agent_backend::{AgentBackend, SpawnRequest};
+use crate::commands;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+/// How long a planning run may take before it is killed
+const PLAN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How often the planning run is checked for a submitted plan and exit
+const PLAN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A proposed subtask
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubtaskProposal {
+    pub title: String,
+    pub description: String,
+    /// Indexes of the proposals this one depends on
+    #[serde(default)]
+    pub depends_on: Vec<usize>,
+}
+
+/// State of a decomposition run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DecompositionStatus {
+    Running,
+    Ready,
+    Failed,
+}
+
+/// A task's proposed breakdown, persisted until the subtasks are created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDecomposition {
+    pub task_id: String,
+    pub status: DecompositionStatus,
+    pub subtasks: Vec<SubtaskProposal>,
+    /// The agent's answer as written, for breakdowns that couldn't be parsed
+    pub raw_output: Option<String>,
+    pub error: Option<String>,
+    pub created_at: u64,
+}
+
+/// Get the directory for decomposition files
+fn get_decompositions_dir() -> PathBuf {
+    let dir = dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".medusa")
+        .join("decompositions");
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+fn decomposition_file(task_id: &str) -> PathBuf {
+    get_decompositions_dir().join(format!("{}.json", task_id))
+}
+
+/// Load the decomposition for a task
+pub fn load(task_id: &str) -> Option<TaskDecomposition> {
+    let content = fs::read_to_string(decomposition_file(task_id)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save(decomposition: &TaskDecomposition) {
+    if let Ok(content) = serde_json::to_string_pretty(decomposition) {
+        let _ = fs::write(decomposition_file(&decomposition.task_id), content);
+    }
+}
+
+/// Remove a task's decomposition
+pub fn remove(task_id: &str) {
+    let _ = fs::remove_file(decomposition_file(task_id));
+}
+
+/// The prompt asking the agent for a breakdown
+pub fn build_prompt(title: &str, description: &str) -> String {
+    format!(
+        "Break the following task down into smaller subtasks that can each be implemented \
+        and reviewed on its own, ideally in parallel. Explore the codebase as needed, but do \
+        not change any files.\n\n\
+        Task: {}\n\n{}\n\n\
+        Answer with a JSON array in a ```json code block. Each element must have a \"title\", \
+        a \"description\" detailed enough for another engineer to implement it, and a \
+        \"depends_on\" array with the 1-based numbers of the subtasks it needs first \
+        (empty when it can start right away).",
+        title, description
+    )
+}
+
+/// Start decomposing a task in the background. Progress is persisted and emitted
+/// as `task-decomposition` events.
+pub fn start(task_id: &str, project_path: &str, prompt: String, backend: Arc<dyn AgentBackend>, app: AppHandle) {
+    let mut decomposition = TaskDecomposition {
+        task_id: task_id.to_string(),
+        status: DecompositionStatus::Running,
+        subtasks: Vec::new(),
+        raw_output: None,
+        error: None,
+        created_at: commands::now(),
+    };
+    save(&decomposition);
+    let _ = app.emit("task-decomposition", &decomposition);
+
+    let project_path = project_path.to_string();
+    thread::spawn(move || {
+        match run_plan(&decomposition.task_id, &project_path, &prompt, backend.as_ref()) {
+            Ok(output) => {
+                decomposition.subtasks = parse_breakdown(&output);
+                if decomposition.subtasks.is_empty() {
+                    decomposition.status = DecompositionStatus::Failed;
+                    decomposition.error = Some("The agent's answer contained no subtasks".to_string());
+                } else {
+                    decomposition.status = DecompositionStatus::Ready;
+                }
+                decomposition.raw_output = Some(output);
+            }
+            Err(e) => {
+                warn!("Decomposition of task {} failed: {}", decomposition.task_id, e);
+                decomposition.status = DecompositionStatus::Failed;
+                decomposition.error = Some(e.to_string());
+            }
+        }
+
+        info!(
+            "Decomposition of task {} finished with {} subtask(s)",
+            decomposition.task_id,
+            decomposition.subtasks.len()
+        );
+        save(&decomposition);
+        let _ = app.emit("task-decomposition", &decomposition);
+    });
+}
+
+/// Run the agent in plan-only mode and return its answer
+fn run_plan(task_id: &str, project_path: &str, prompt: &str, backend: &dyn AgentBackend) -> Result<String> {
+    let mut child = backend.spawn_plan(&SpawnRequest {
+        task_id,
+        worktree_path: project_path,
+        resume_session_id: None,
+        prompt,
+        model: None,
+        extra_args: &[],
+    })?;
+    drop(child.stdin.take());
+
+    // Drain the pipes on their own threads so a chatty agent can't fill them
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let started = Instant::now();
+    let mut submitted_plan = None;
+
+    let status = loop {
+        // The agent submitted its breakdown as a plan: keep it and tell the agent to stop
+        if submitted_plan.is_none() {
+            if let Ok(Some(plan)) = commands::load_task_plan(task_id) {
+                submitted_plan = Some(plan.content);
+                let _ = commands::write_task_plan_response(
+                    task_id,
+                    false,
+                    Some("Thanks, the breakdown has been captured. Stop here without making changes.".to_string()),
+                );
+            }
+        }
+
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if started.elapsed() > PLAN_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow::anyhow!("The agent took longer than {} minutes", PLAN_TIMEOUT.as_secs() / 60));
+        }
+
+        thread::sleep(PLAN_POLL_INTERVAL);
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if let Some(plan) = submitted_plan {
+        return Ok(plan);
+    }
+    if !status.success() {
+        return Err(anyhow::anyhow!("The agent exited with {}: {}", status, stderr.trim()));
+    }
+
+    Ok(backend.plan_result(&stdout))
+}
+
+/// Read a pipe to the end on a separate thread
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut output);
+        }
+        output
+    })
+}
+
+/// Parse an agent's breakdown: a JSON array (preferably in a ```json block), or
+/// failing that, the top-level items of a markdown list
+pub fn parse_breakdown(text: &str) -> Vec<SubtaskProposal> {
+    parse_json_breakdown(text).unwrap_or_else(|| parse_markdown_breakdown(text))
+}
+
+fn parse_json_breakdown(text: &str) -> Option<Vec<SubtaskProposal>> {
+    let json = match text.find("```json") {
+        Some(start) => {
+            let body = &text[start + "```json".len()..];
+            &body[..body.find("```").unwrap_or(body.len())]
+        }
+        None => &text[text.find('[')?..=text.rfind(']')?],
+    };
+
+    let items: Vec<serde_json::Value> = serde_json::from_str(json.trim()).ok()?;
+    let titles: Vec<Option<String>> = items
+        .iter()
+        .map(|item| item.get("title").and_then(|t| t.as_str()).map(|t| t.trim().to_string()))
+        .collect();
+
+    let proposals: Vec<SubtaskProposal> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let title = titles[index].clone().filter(|t| !t.is_empty())?;
+            let description = item.get("description").and_then(|d| d.as_str()).unwrap_or_default();
+
+            // Dependencies are 1-based numbers (or titles) of other subtasks
+            let depends_on = item
+                .get("depends_on")
+                .and_then(|d| d.as_array())
+                .map(|deps| {
+                    deps.iter()
+                        .filter_map(|dep| match dep {
+                            serde_json::Value::Number(n) => n.as_u64().and_then(|n| (n as usize).checked_sub(1)),
+                            serde_json::Value::String(s) => titles.iter().position(|t| t.as_deref() == Some(s.trim())),
+                            _ => None,
+                        })
+                        .filter(|&dep| dep != index && dep < items.len())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(SubtaskProposal {
+                title,
+                description: description.trim().to_string(),
+                depends_on,
+            })
+        })
+        .collect();
+
+    if proposals.is_empty() {
+        None
+    } else {
+        Some(proposals)
+    }
+}
+
+fn parse_markdown_breakdown(text: &str) -> Vec<SubtaskProposal> {
+    let mut proposals: Vec<SubtaskProposal> = Vec::new();
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        match list_item(line) {
+            Some(item) => {
+                let (title, description) = split_title(item);
+                proposals.push(SubtaskProposal {
+                    title,
+                    description,
+                    depends_on: Vec::new(),
+                });
+            }
+            None => {
+                // Indented lines continue the current item; anything else ends it
+                let Some(current) = proposals.last_mut() else {
+                    continue;
+                };
+                if line.starts_with(' ') || line.starts_with('\t') {
+                    let detail = line.trim().trim_start_matches(['-', '*']).trim();
+                    if !detail.is_empty() {
+                        if !current.description.is_empty() {
+                            current.description.push('\n');
+                        }
+                        current.description.push_str(detail);
+                    }
+                } else if !line.trim().is_empty() && !line.starts_with('#') {
+                    break;
+                }
+            }
+        }
+    }
+
+    proposals
+}
+
+/// The text of a top-level list item ("1. ", "1) ", "- " or "* ")
+fn list_item(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(rest.trim());
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))
+        .map(str::trim)
+}
+
+/// Split "**Title** - details" or "Title: details" into title and description
+fn split_title(item: &str) -> (String, String) {
+    if let Some(rest) = item.strip_prefix("**") {
+        if let Some(end) = rest.find("**") {
+            let title = rest[..end].trim().trim_end_matches(':');
+            let description = rest[end + 2..].trim().trim_start_matches([':', '-', '—']).trim();
+            return (title.to_string(), description.to_string());
+        }
+    }
+
+    for separator in [" - ", " — ", ": "] {
+        if let Some((title, description)) = item.split_once(separator) {
+            return (title.trim().to_string(), description.trim().to_string());
+        }
+    }
+
+    (item.to_string(), String::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_breakdown() {
+        let text = r#"Here is the breakdown:
+
+```json
+[
+  {"title": "Add schema", "description": "Create the table", "depends_on": []},
+  {"title": "Add API", "description": "Expose endpoints", "depends_on": [1]},
+  {"title": "Add UI", "description": "Build the page", "depends_on": ["Add API", 3]}
+]
+```"#;
+        let proposals = parse_breakdown(text);
+
+        assert_eq!(proposals.len(), 3);
+        assert_eq!(proposals[0].title, "Add schema");
+        assert_eq!(proposals[1].depends_on, vec![0]);
+        // Self-references are dropped
+        assert_eq!(proposals[2].depends_on, vec![1]);
+    }
+
+    #[test]
+    fn test_parse_markdown_breakdown() {
+        let text = "Plan:\n\n\
+            1. **Add schema** - Create the table\n   \
+               - with an index\n\
+            2. Add API: Expose endpoints\n\
+            3. Add UI\n\n\
+            Let me know if this works.";
+        let proposals = parse_breakdown(text);
+
+        assert_eq!(proposals.len(), 3);
+        assert_eq!(proposals[0].title, "Add schema");
+        assert_eq!(proposals[0].description, "Create the table\nwith an index");
+        assert_eq!(proposals[1].title, "Add API");
+        assert_eq!(proposals[1].description, "Expose endpoints");
+        assert_eq!(proposals[2].title, "Add UI");
+    }
+}
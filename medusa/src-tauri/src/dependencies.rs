@@ -0,0 +1,124 @@
+//! Task Dependencies - Ordering tasks as a DAG
+//!
+//! A task can declare `depends_on` other tasks in the same project. It becomes
+//! startable once every prerequisite is merged (`Done`), and its worktree then
+//! branches from the branch they were merged into. With `branch_from_dependency`
+//! a task may instead start from a single prerequisite's task branch as soon as
+//! that prerequisite is in review, so chained work doesn't wait for the merge.
+
+use crate::commands::{KanbanTask, TaskStatus};
+use crate::task_agent::StartPoint;
+use std::collections::{HashMap, HashSet};
+
+/// Whether a task's prerequisites allow it to start
+#[derive(Debug, Clone, PartialEq)]
+pub enum Readiness {
+    /// Ready; the worktree should start from `start_point` (None: the project's current branch)
+    Ready(Option<StartPoint>),
+    /// Waiting on these prerequisite task IDs
+    Blocked(Vec<String>),
+}
+
+/// Find a cycle that setting `task_id`'s dependencies to `depends_on` would create.
+/// `graph` maps task IDs to their current dependencies. Returns the cycle as a path
+/// starting and ending at `task_id`.
+pub fn find_cycle(graph: &HashMap<String, Vec<String>>, task_id: &str, depends_on: &[String]) -> Option<Vec<String>> {
+    fn visit(
+        graph: &HashMap<String, Vec<String>>,
+        target: &str,
+        node: &str,
+        path: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+    ) -> bool {
+        path.push(node.to_string());
+        if node == target {
+            return true;
+        }
+        if seen.insert(node.to_string()) {
+            for next in graph.get(node).map(Vec::as_slice).unwrap_or_default() {
+                if visit(graph, target, next, path, seen) {
+                    return true;
+                }
+            }
+        }
+        path.pop();
+        false
+    }
+
+    let mut seen = HashSet::new();
+    for dependency in depends_on {
+        let mut path = vec![task_id.to_string()];
+        if visit(graph, task_id, dependency, &mut path, &mut seen) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Decide whether `task` can start given the current state of its prerequisites.
+/// Prerequisites that no longer exist are ignored.
+pub fn readiness(task: &KanbanTask, tasks: &HashMap<&str, &KanbanTask>) -> Readiness {
+    let prerequisites: Vec<&KanbanTask> = task.depends_on
+        .iter()
+        .filter_map(|id| tasks.get(id.as_str()).copied())
+        .collect();
+    if prerequisites.is_empty() {
+        return Readiness::Ready(None);
+    }
+
+    let pending: Vec<&KanbanTask> = prerequisites
+        .iter()
+        .filter(|t| t.status != TaskStatus::Done)
+        .copied()
+        .collect();
+
+    if pending.is_empty() {
+        // Start from the branch the prerequisites were merged into
+        return Readiness::Ready(
+            prerequisites
+                .iter()
+                .find_map(|t| t.base_branch.clone())
+                .map(|branch| StartPoint { base_branch: branch.clone(), start_ref: branch }),
+        );
+    }
+
+    // Chain off a single prerequisite that has finished its work but isn't merged yet
+    if let [prerequisite] = pending.as_slice() {
+        if task.branch_from_dependency && prerequisite.status == TaskStatus::Review {
+            if let (Some(branch), Some(base_branch)) = (&prerequisite.branch, &prerequisite.base_branch) {
+                return Readiness::Ready(Some(StartPoint {
+                    base_branch: base_branch.clone(),
+                    start_ref: branch.clone(),
+                }));
+            }
+        }
+    }
+
+    Readiness::Blocked(pending.iter().map(|t| t.id.clone()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        edges
+            .iter()
+            .map(|(id, deps)| (id.to_string(), deps.iter().map(|d| d.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let g = graph(&[("a", &[]), ("b", &["a"]), ("c", &["b"])]);
+
+        // a -> c -> b -> a
+        assert_eq!(
+            find_cycle(&g, "a", &["c".to_string()]),
+            Some(vec!["a".to_string(), "c".to_string(), "b".to_string(), "a".to_string()])
+        );
+        assert_eq!(find_cycle(&g, "a", &["a".to_string()]), Some(vec!["a".to_string(), "a".to_string()]));
+        assert_eq!(find_cycle(&g, "c", &["a".to_string()]), None);
+        assert_eq!(find_cycle(&g, "d", &["c".to_string(), "a".to_string()]), None);
+    }
+}
@@ -1,2 +1,2 @@
 mod repository;
-pub use repository::{GitManager, WorktreeInfo};
+pub use repository::{DiffStats, GitManager, WorktreeInfo};
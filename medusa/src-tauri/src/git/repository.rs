@@ -1,4 +1,5 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -14,6 +15,14 @@ pub struct WorktreeInfo {
     pub is_main: bool,
 }
 
+/// Size of a set of changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
 impl GitManager {
     pub fn new(repo_path: String) -> Result<Self> {
         // Verify it's a git repository
@@ -173,7 +182,7 @@ impl GitManager {
     }
 
     /// Get the .medusa-worktrees directory path
-    fn worktrees_dir(&self) -> PathBuf {
+    pub fn worktrees_dir(&self) -> PathBuf {
         PathBuf::from(&self.repo_path).join(".medusa-worktrees")
     }
 
@@ -202,9 +211,29 @@ impl GitManager {
         }
     }
 
+    /// Resolve a branch, tag or commit to a full commit hash
+    pub fn resolve_commit(&self, rev: &str) -> Result<String> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])
+            .current_dir(&self.repo_path)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("Failed to resolve {}", rev));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     /// Create a new worktree for a task
     /// Returns the path to the created worktree
     pub fn create_worktree(&self, task_id: &str, branch_name: &str) -> Result<PathBuf> {
+        self.create_worktree_from(task_id, branch_name, None)
+    }
+
+    /// Create a new worktree for a task, branching from `start_point` (default: HEAD)
+    /// Returns the path to the created worktree
+    pub fn create_worktree_from(&self, task_id: &str, branch_name: &str, start_point: Option<&str>) -> Result<PathBuf> {
         let worktrees_dir = self.worktrees_dir();
 
         // Create .medusa-worktrees directory if it doesn't exist
@@ -243,8 +272,12 @@ impl GitManager {
         }
 
         // Create the worktree with a new branch
+        let mut args = vec!["worktree", "add", "-b", branch_name, worktree_path.to_str().unwrap()];
+        if let Some(start_point) = start_point {
+            args.push(start_point);
+        }
         let output = Command::new("git")
-            .args(["worktree", "add", "-b", branch_name, worktree_path.to_str().unwrap()])
+            .args(&args)
             .current_dir(&self.repo_path)
             .output()?;
 
@@ -264,6 +297,39 @@ impl GitManager {
         Ok(worktree_path)
     }
 
+    /// Move a task's worktree to another task ID's location
+    pub fn move_worktree(&self, from_task_id: &str, to_task_id: &str) -> Result<PathBuf> {
+        let from_path = self.worktrees_dir().join(from_task_id);
+        let to_path = self.worktrees_dir().join(to_task_id);
+
+        let output = Command::new("git")
+            .args(["worktree", "move", from_path.to_str().unwrap(), to_path.to_str().unwrap()])
+            .current_dir(&self.repo_path)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Failed to move worktree: {}", stderr));
+        }
+
+        Ok(to_path)
+    }
+
+    /// Rename a branch (also when it is checked out in a worktree)
+    pub fn rename_branch(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["branch", "-m", old_name, new_name])
+            .current_dir(&self.repo_path)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Failed to rename branch: {}", stderr));
+        }
+
+        Ok(())
+    }
+
     /// Remove a worktree
     pub fn remove_worktree(&self, task_id: &str) -> Result<()> {
         let worktree_path = self.worktrees_dir().join(task_id);
@@ -326,6 +392,50 @@ impl GitManager {
         Ok(worktrees)
     }
 
+    /// Remove git's metadata for worktrees whose directories no longer exist
+    pub fn prune_worktrees(&self) -> Result<()> {
+        let output = Command::new("git")
+            .args(["worktree", "prune"])
+            .current_dir(&self.repo_path)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Failed to prune worktrees: {}", stderr));
+        }
+
+        Ok(())
+    }
+
+    /// List local branches under a prefix (e.g. "medusa/")
+    pub fn list_branches(&self, prefix: &str) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .args(["for-each-ref", "--format=%(refname:short)", &format!("refs/heads/{}", prefix)])
+            .current_dir(&self.repo_path)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Failed to list branches: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    /// Check if a local branch exists
+    pub fn branch_exists(&self, branch_name: &str) -> bool {
+        Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch_name)])
+            .current_dir(&self.repo_path)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
     /// Get the worktree path for a task
     pub fn get_worktree_path(&self, task_id: &str) -> Option<PathBuf> {
         let worktree_path = self.worktrees_dir().join(task_id);
@@ -472,6 +582,53 @@ impl GitManager {
         Ok(result)
     }
 
+    /// Line counts of all changes (committed + uncommitted) vs a base commit.
+    /// New untracked files count as changed files with all their lines inserted.
+    pub fn get_worktree_diff_stats(&self, task_id: &str, base_commit: &str) -> Result<DiffStats> {
+        let worktree_path = self.worktrees_dir().join(task_id);
+
+        if !worktree_path.exists() {
+            return Err(anyhow::anyhow!("Worktree not found"));
+        }
+
+        let output = Command::new("git")
+            .args(["diff", "--numstat", base_commit])
+            .current_dir(&worktree_path)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Failed to get diff stats: {}", stderr));
+        }
+
+        let mut stats = DiffStats::default();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let mut parts = line.split('\t');
+            // Binary files show "-" for both counts
+            let insertions = parts.next().and_then(|n| n.parse::<usize>().ok()).unwrap_or(0);
+            let deletions = parts.next().and_then(|n| n.parse::<usize>().ok()).unwrap_or(0);
+            stats.files_changed += 1;
+            stats.insertions += insertions;
+            stats.deletions += deletions;
+        }
+
+        let output = Command::new("git")
+            .args(["ls-files", "--others", "--exclude-standard"])
+            .current_dir(&worktree_path)
+            .output()?;
+
+        if output.status.success() {
+            for file in String::from_utf8_lossy(&output.stdout).lines().filter(|l| !l.is_empty()) {
+                stats.files_changed += 1;
+                stats.insertions += std::fs::read_to_string(worktree_path.join(file))
+                    .map(|content| content.lines().count())
+                    .unwrap_or(0);
+            }
+        }
+
+        Ok(stats)
+    }
+
     /// Get file diff vs a base commit including both committed AND uncommitted changes
     pub fn get_file_diff_vs_base(&self, task_id: &str, file_path: &str, base_commit: Option<&str>) -> Result<String> {
         let worktree_path = self.worktrees_dir().join(task_id);
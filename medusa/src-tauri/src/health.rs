@@ -0,0 +1,420 @@
+//! Health - Reconciles task state with what is actually on disk
+//!
+//! After a crash the database can drift from reality: tasks stay `InProgress`
+//! with an `agent_pid` that points at nothing, `worktree_path` points at a
+//! deleted directory, and `.medusa-worktrees` holds worktrees no task (or task
+//! attempt) owns.
+//! The reconciliation pass cross-checks tasks, git worktrees, `medusa/task-*`
+//! branches, session files and live processes. It fixes what it safely can and
+//! reports the rest.
+
+use crate::attempts::TaskAttempt;
+use crate::commands::{self, KanbanTask, TaskStatus};
+use crate::git::GitManager;
+use crate::supervisor;
+use crate::task_agent::{self, TaskAgentStatus, TASK_AGENT_MANAGER};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// Prefix of the branches Medusa creates for tasks
+const TASK_BRANCH_PREFIX: &str = "medusa/task-";
+
+/// Last report, kept in memory for the UI
+static LAST_REPORT: Mutex<Option<HealthReport>> = Mutex::new(None);
+
+/// Kind of inconsistency found
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HealthIssueKind {
+    /// Task records an agent PID but no agent is running
+    DeadAgent,
+    /// Task's worktree directory no longer exists
+    MissingWorktree,
+    /// Git still tracks a worktree whose directory was deleted
+    StaleWorktreeMetadata,
+    /// Worktree (or leftover directory) in the worktrees folder with no task
+    OrphanWorktree,
+    /// `medusa/task-*` branch with no task
+    OrphanBranch,
+    /// Session or supervisor files for a task that no longer exists
+    OrphanSession,
+}
+
+/// A single inconsistency and whether it was fixed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    pub task_id: Option<String>,
+    pub project_path: Option<String>,
+    pub path: Option<String>,
+    pub message: String,
+    pub fixed: bool,
+}
+
+/// Result of a reconciliation pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub checked_at: u64,
+    pub tasks_checked: usize,
+    pub issues: Vec<HealthIssue>,
+}
+
+impl HealthReport {
+    /// Issues that still need the user's attention
+    pub fn unresolved(&self) -> usize {
+        self.issues.iter().filter(|i| !i.fixed).count()
+    }
+}
+
+fn issue(kind: HealthIssueKind, task: Option<&KanbanTask>, path: Option<&Path>, message: String, fixed: bool) -> HealthIssue {
+    HealthIssue {
+        kind,
+        task_id: task.map(|t| t.id.clone()),
+        project_path: task.map(|t| t.project_path.clone()),
+        path: path.map(|p| p.to_string_lossy().to_string()),
+        message,
+        fixed,
+    }
+}
+
+/// Task branches that no task claims, either by stored branch name or by ID prefix
+/// (attempt branches `medusa/task-<id>-<label>` belong to their task)
+pub fn find_orphan_branches(branches: &[String], tasks: &[KanbanTask]) -> Vec<String> {
+    let known: HashSet<&str> = tasks.iter().filter_map(|t| t.branch.as_deref()).collect();
+
+    branches
+        .iter()
+        .filter(|branch| branch.starts_with(TASK_BRANCH_PREFIX))
+        .filter(|branch| !known.contains(branch.as_str()))
+        .filter(|branch| {
+            let short_id = branch[TASK_BRANCH_PREFIX.len()..].split('-').next().unwrap_or_default();
+            !tasks.iter().any(|t| t.id.starts_with(short_id))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Run the reconciliation pass and remember the report
+pub fn run_reconciliation() -> Result<HealthReport, String> {
+    let tasks = commands::load_all_tasks()?;
+    let attempts = crate::attempts::load_all_attempts()?;
+    let mut issues = Vec::new();
+
+    for task in &tasks {
+        check_agent(task, &mut issues);
+    }
+
+    // Group tasks by project so each repository is inspected once
+    let mut projects: BTreeMap<&str, Vec<&KanbanTask>> = BTreeMap::new();
+    for task in &tasks {
+        projects.entry(task.project_path.as_str()).or_default().push(task);
+    }
+
+    for (project_path, project_tasks) in &projects {
+        let git = match GitManager::new(project_path.to_string()) {
+            Ok(git) => git,
+            Err(e) => {
+                warn!("Skipping health check for {}: {}", project_path, e);
+                continue;
+            }
+        };
+        check_worktrees(&git, project_path, project_tasks, &attempts, &mut issues);
+        check_branches(&git, project_path, &tasks, &mut issues);
+    }
+
+    check_session_files(&tasks, &attempts, &mut issues);
+
+    let report = HealthReport {
+        checked_at: commands::now(),
+        tasks_checked: tasks.len(),
+        issues,
+    };
+
+    info!(
+        "Health check: {} task(s), {} issue(s), {} unresolved",
+        report.tasks_checked,
+        report.issues.len(),
+        report.unresolved()
+    );
+
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report.clone());
+    }
+
+    Ok(report)
+}
+
+/// Clear agent PIDs of tasks whose agent is gone
+fn check_agent(task: &KanbanTask, issues: &mut Vec<HealthIssue>) {
+    let Some(pid) = task.agent_pid else {
+        return;
+    };
+
+    let running = TASK_AGENT_MANAGER
+        .lock()
+        .ok()
+        .and_then(|m| m.get_agent(&task.id))
+        .map(|a| a.status == TaskAgentStatus::Running)
+        .unwrap_or(false);
+    let supervised = supervisor::load_state(&task.id)
+        .map(|s| supervisor::is_live(&s))
+        .unwrap_or(false);
+
+    if running || supervised || supervisor::is_process_alive(pid) {
+        return;
+    }
+
+    let fixed = commands::clear_agent_pid(&task.id).is_ok();
+    issues.push(issue(
+        HealthIssueKind::DeadAgent,
+        Some(task),
+        None,
+        if task.status == TaskStatus::InProgress {
+            format!("Agent (PID {}) is no longer running; task paused", pid)
+        } else {
+            format!("Cleared stale agent PID {}", pid)
+        },
+        fixed,
+    ));
+}
+
+/// Check task worktrees against git's view of the repository
+fn check_worktrees(
+    git: &GitManager,
+    project_path: &str,
+    tasks: &[&KanbanTask],
+    attempts: &[TaskAttempt],
+    issues: &mut Vec<HealthIssue>,
+) {
+    let worktrees = match git.list_worktrees() {
+        Ok(worktrees) => worktrees,
+        Err(e) => {
+            warn!("Failed to list worktrees for {}: {}", project_path, e);
+            return;
+        }
+    };
+
+    // Stale git metadata is always safe to prune
+    let stale: Vec<_> = worktrees.iter().filter(|w| !w.is_main && !w.path.exists()).collect();
+    if !stale.is_empty() {
+        let fixed = git.prune_worktrees().is_ok();
+        for worktree in stale {
+            issues.push(HealthIssue {
+                kind: HealthIssueKind::StaleWorktreeMetadata,
+                task_id: None,
+                project_path: Some(project_path.to_string()),
+                path: Some(worktree.path.to_string_lossy().to_string()),
+                message: format!("Git tracked a deleted worktree for {}", worktree.branch),
+                fixed,
+            });
+        }
+    }
+
+    // Tasks whose worktree directory is gone
+    for task in tasks {
+        let Some(worktree_path) = task.worktree_path.as_deref() else {
+            continue;
+        };
+        if task.status == TaskStatus::Done || Path::new(worktree_path).exists() {
+            continue;
+        }
+
+        let branch = task.branch.as_deref().filter(|b| git.branch_exists(b));
+        let (message, fixed) = match branch {
+            Some(branch) => match git.create_worktree(&task.id, branch) {
+                Ok(path) => (
+                    format!("Worktree was missing; recreated from {}", branch),
+                    commands::set_task_worktree_path(&task.id, &path.to_string_lossy()).is_ok(),
+                ),
+                Err(e) => (format!("Worktree is missing and could not be recreated: {}", e), false),
+            },
+            None => ("Worktree and branch are both gone; the task's changes cannot be recovered".to_string(), false),
+        };
+
+        issues.push(issue(HealthIssueKind::MissingWorktree, Some(task), Some(Path::new(worktree_path)), message, fixed));
+    }
+
+    // Worktrees in the worktrees folder that no task owns
+    let owned: HashSet<PathBuf> = tasks
+        .iter()
+        .filter_map(|t| t.worktree_path.as_deref())
+        .chain(attempts.iter().map(|a| a.worktree_path.as_str()))
+        .map(PathBuf::from)
+        .collect();
+    let worktrees_dir = git.worktrees_dir();
+
+    let mut candidates: Vec<PathBuf> = worktrees
+        .iter()
+        .filter(|w| !w.is_main && w.path.starts_with(&worktrees_dir) && w.path.exists())
+        .map(|w| w.path.clone())
+        .collect();
+    if let Ok(entries) = fs::read_dir(&worktrees_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && !candidates.contains(&path) {
+                candidates.push(path);
+            }
+        }
+    }
+
+    for path in candidates {
+        if owned.contains(&path) {
+            continue;
+        }
+        issues.push(HealthIssue {
+            kind: HealthIssueKind::OrphanWorktree,
+            task_id: None,
+            project_path: Some(project_path.to_string()),
+            path: Some(path.to_string_lossy().to_string()),
+            message: "Worktree has no task; review and remove it if it holds nothing you need".to_string(),
+            fixed: false,
+        });
+    }
+}
+
+/// Report task branches with no task
+fn check_branches(git: &GitManager, project_path: &str, tasks: &[KanbanTask], issues: &mut Vec<HealthIssue>) {
+    let branches = match git.list_branches(TASK_BRANCH_PREFIX) {
+        Ok(branches) => branches,
+        Err(e) => {
+            warn!("Failed to list branches for {}: {}", project_path, e);
+            return;
+        }
+    };
+
+    for branch in find_orphan_branches(&branches, tasks) {
+        issues.push(HealthIssue {
+            kind: HealthIssueKind::OrphanBranch,
+            task_id: None,
+            project_path: Some(project_path.to_string()),
+            path: None,
+            message: format!("Branch {} has no task", branch),
+            fixed: false,
+        });
+    }
+}
+
+/// Remove supervisor leftovers and report session files of deleted tasks
+fn check_session_files(tasks: &[KanbanTask], attempts: &[TaskAttempt], issues: &mut Vec<HealthIssue>) {
+    let task_ids: HashSet<&str> = tasks
+        .iter()
+        .map(|t| t.id.as_str())
+        .chain(attempts.iter().map(|a| a.id.as_str()))
+        .collect();
+
+    for task_id in supervisor::list_task_ids() {
+        if task_ids.contains(task_id.as_str()) {
+            continue;
+        }
+        let live = supervisor::load_state(&task_id).map(|s| supervisor::is_live(&s)).unwrap_or(false);
+        if !live {
+            supervisor::remove_files(&task_id);
+        }
+        issues.push(HealthIssue {
+            kind: HealthIssueKind::OrphanSession,
+            task_id: Some(task_id),
+            project_path: None,
+            path: None,
+            message: if live {
+                "An agent is still running for a deleted task".to_string()
+            } else {
+                "Removed supervisor files of a deleted task".to_string()
+            },
+            fixed: !live,
+        });
+    }
+
+    let mut orphans: BTreeMap<String, PathBuf> = BTreeMap::new();
+    if let Ok(entries) = fs::read_dir(task_agent::get_sessions_dir()) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = name
+                .strip_suffix(".jsonl")
+                .or_else(|| name.strip_suffix(".session_id"));
+            if let Some(id) = id {
+                if !task_ids.contains(id) {
+                    orphans.entry(id.to_string()).or_insert_with(|| entry.path());
+                }
+            }
+        }
+    }
+
+    for (task_id, path) in orphans {
+        issues.push(HealthIssue {
+            kind: HealthIssueKind::OrphanSession,
+            task_id: Some(task_id),
+            project_path: None,
+            path: Some(path.to_string_lossy().to_string()),
+            message: "Session history belongs to a deleted task".to_string(),
+            fixed: false,
+        });
+    }
+}
+
+/// Get the last health report, optionally running a fresh check
+#[tauri::command]
+pub async fn get_health_report(refresh: Option<bool>) -> Result<HealthReport, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some(report) = LAST_REPORT.lock().ok().and_then(|r| r.clone()) {
+            return Ok(report);
+        }
+    }
+
+    run_reconciliation().map_err(|e| format!("Failed to run health check: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, branch: Option<&str>) -> KanbanTask {
+        KanbanTask {
+            id: id.to_string(),
+            title: String::new(),
+            description: String::new(),
+            status: TaskStatus::InProgress,
+            project_path: "/repo".to_string(),
+            branch: branch.map(|b| b.to_string()),
+            worktree_path: None,
+            plan_id: None,
+            agent_pid: None,
+            session_id: None,
+            agent_backend: None,
+            base_commit: None,
+            base_branch: None,
+            priority: 0,
+            queued_at: None,
+            depends_on: Vec::new(),
+            branch_from_dependency: false,
+            parent_id: None,
+            verification_status: None,
+            started_at: None,
+            completed_at: None,
+            files_changed: None,
+            diff_summary: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_find_orphan_branches() {
+        let tasks = vec![
+            task("aaaaaaaa-1111", Some("medusa/task-aaaaaaaa")),
+            task("bbbbbbbb-2222", None),
+        ];
+        let branches = vec![
+            "medusa/task-aaaaaaaa".to_string(),
+            "medusa/task-bbbbbbbb".to_string(),
+            "medusa/task-cccccccc".to_string(),
+            "medusa/task-bbbbbbbb-a".to_string(),
+            "medusa/task-cccccccc-b".to_string(),
+            "medusa/other".to_string(),
+        ];
+
+        assert_eq!(find_orphan_branches(&branches, &tasks), vec!["medusa/task-cccccccc".to_string(), "medusa/task-cccccccc-b".to_string()]);
+    }
+}
@@ -1,8 +1,14 @@
+pub mod agent_backend;
+pub mod attempts;
 pub mod commands;
+pub mod decompose;
+pub mod dependencies;
 pub mod git;
+pub mod health;
 pub mod logging;
 pub mod setup;
 pub mod state;
+pub mod supervisor;
 pub mod task_agent;
 
 pub use state::AppState;
@@ -65,6 +71,17 @@ pub fn run() {
             // Task plan review commands
             commands::get_task_plan,
             commands::respond_to_task_plan,
+            // Task decomposition commands
+            commands::decompose_task,
+            commands::get_task_decomposition,
+            commands::create_subtasks,
+            // Parallel attempt commands
+            commands::start_task_attempts,
+            commands::get_task_attempts,
+            commands::compare_task_attempts,
+            commands::stop_task_attempt,
+            commands::discard_task_attempt,
+            commands::merge_task_attempt,
             // Review commands
             commands::merge_task,
             commands::reject_task,
@@ -77,6 +94,8 @@ pub fn run() {
             setup::auto_setup,
             setup::reinstall_setup,
             setup::update_hook_settings,
+            // Health commands
+            health::get_health_report,
         ])
         .setup(|app| {
             // Run auto-setup on app start
@@ -84,6 +103,23 @@ pub fn run() {
                 tracing::warn!("Auto-setup failed: {}", e);
             }
 
+            // Re-attach to agents that kept running while the app was closed
+            commands::reattach_agents(app.handle().clone());
+
+            // Start queued tasks whenever an agent slot frees up, and drain what's queued now
+            if let Ok(manager) = task_agent::TASK_AGENT_MANAGER.lock() {
+                manager.on_slot_freed(commands::drain_agent_queue);
+            }
+            let handle = app.handle().clone();
+            std::thread::spawn(move || commands::drain_agent_queue(handle));
+
+            // Reconcile task state with disk in the background (git calls can be slow)
+            std::thread::spawn(|| {
+                if let Err(e) = health::run_reconciliation() {
+                    tracing::warn!("Health check failed: {}", e);
+                }
+            });
+
             let window = app.get_webview_window("main").unwrap();
             window.maximize().unwrap();
             Ok(())
@@ -92,9 +128,9 @@ pub fn run() {
         .expect("error while building tauri application")
         .run(|_app_handle, event| {
             if let RunEvent::Exit = event {
-                // Stop all running agents when app exits (Ctrl+C or window close)
-                tracing::info!("App exiting, cleaning up agents...");
-                task_agent::shutdown_all_agents();
+                // Agents keep running under their supervisors (Ctrl+C or window close)
+                tracing::info!("App exiting, detaching from agents...");
+                task_agent::detach_all_agents();
             }
         });
 }
@@ -2,5 +2,11 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Supervisor mode: `medusa --agent-supervisor <task_id>` runs one detached task agent
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 3 && args[1] == medusa_lib::supervisor::SUPERVISOR_FLAG {
+        std::process::exit(medusa_lib::supervisor::run(&args[2]));
+    }
+
     medusa_lib::run();
 }
@@ -0,0 +1,398 @@
+//! Agent Supervisor - Keeps task agents alive across app restarts
+//!
+//! Each agent runs under a small detached supervisor (the Medusa binary started
+//! with `--agent-supervisor <task_id>`). The supervisor owns the agent's stdio
+//! pipes, persists its output to the session file and forwards messages the app
+//! appends to an inbox file. Its state file tells the app whether the agent is
+//! still running, so a restarted app can re-attach to it.
+//!
+//! Files live in `~/.medusa/supervisors/`:
+//! - `<task_id>.spec.json`  - what to launch (written by the app)
+//! - `<task_id>.state.json` - supervisor/agent PIDs and status (written by the supervisor)
+//! - `<task_id>.inbox`      - encoded stdin lines (appended by the app)
+//! - `<task_id>.stderr`     - agent stderr
+
+use crate::agent_backend::{backend_for, AgentEvent, SpawnRequest};
+use crate::task_agent::{append_to_session_file, save_session_id, TaskAgentStatus};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// Command-line flag that starts the binary in supervisor mode
+pub const SUPERVISOR_FLAG: &str = "--agent-supervisor";
+
+/// How often the supervisor polls the inbox for new messages
+const INBOX_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// What the supervisor should launch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorSpec {
+    pub task_id: String,
+    pub backend: String,
+    pub agent_command: Option<String>,
+    pub worktree_path: String,
+    pub resume_session_id: Option<String>,
+    pub prompt: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// Supervisor state, persisted so a restarted app can re-attach
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorState {
+    pub task_id: String,
+    pub supervisor_pid: u32,
+    pub agent_pid: Option<u32>,
+    pub status: TaskAgentStatus,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub updated_at: i64,
+}
+
+/// Get the directory for supervisor files
+fn get_supervisors_dir() -> PathBuf {
+    let dir = dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".medusa")
+        .join("supervisors");
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+fn spec_file(task_id: &str) -> PathBuf {
+    get_supervisors_dir().join(format!("{}.spec.json", task_id))
+}
+
+fn state_file(task_id: &str) -> PathBuf {
+    get_supervisors_dir().join(format!("{}.state.json", task_id))
+}
+
+fn inbox_file(task_id: &str) -> PathBuf {
+    get_supervisors_dir().join(format!("{}.inbox", task_id))
+}
+
+/// Path of the file the supervisor writes agent stderr to
+pub fn stderr_file(task_id: &str) -> PathBuf {
+    get_supervisors_dir().join(format!("{}.stderr", task_id))
+}
+
+/// Load the supervisor state for a task
+pub fn load_state(task_id: &str) -> Option<SupervisorState> {
+    let content = fs::read_to_string(state_file(task_id)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_state(state: &SupervisorState) {
+    // Write to a temp file and rename so readers never see a partial file
+    let path = state_file(&state.task_id);
+    let tmp = path.with_extension("json.tmp");
+    if let Ok(content) = serde_json::to_string(state) {
+        if fs::write(&tmp, content).is_ok() {
+            let _ = fs::rename(&tmp, &path);
+        }
+    }
+}
+
+/// Remove all supervisor files for a task
+pub fn remove_files(task_id: &str) {
+    let _ = fs::remove_file(spec_file(task_id));
+    let _ = fs::remove_file(state_file(task_id));
+    let _ = fs::remove_file(inbox_file(task_id));
+    let _ = fs::remove_file(stderr_file(task_id));
+}
+
+/// Task IDs that have supervisor files on disk
+pub fn list_task_ids() -> Vec<String> {
+    let mut ids = Vec::new();
+    if let Ok(entries) = fs::read_dir(get_supervisors_dir()) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(id) = name.strip_suffix(".state.json") {
+                ids.push(id.to_string());
+            }
+        }
+    }
+    ids
+}
+
+/// Check whether a process is still alive
+pub fn is_process_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+
+    #[cfg(windows)]
+    {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()))
+            .unwrap_or(false)
+    }
+}
+
+/// Whether the supervisor for this state is still running its agent
+pub fn is_live(state: &SupervisorState) -> bool {
+    state.status == TaskAgentStatus::Running && is_process_alive(state.supervisor_pid)
+}
+
+/// Append an encoded line to the supervisor's inbox (forwarded to agent stdin)
+pub fn send_input(task_id: &str, line: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(inbox_file(task_id))?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Launch a detached supervisor and wait until it has spawned the agent
+pub fn launch(spec: &SupervisorSpec) -> Result<SupervisorState> {
+    let task_id = &spec.task_id;
+
+    // Start from a clean slate for this task
+    remove_files(task_id);
+    File::create(inbox_file(task_id))?;
+    fs::write(spec_file(task_id), serde_json::to_string_pretty(spec)?)?;
+
+    let exe = std::env::current_exe()?;
+    let mut cmd = Command::new(exe);
+    cmd.args([SUPERVISOR_FLAG, task_id])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Own process group, so the supervisor survives the app exiting
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let supervisor = cmd.spawn()?;
+    let supervisor_pid = supervisor.id();
+    info!("Launched supervisor (PID {}) for task {}", supervisor_pid, task_id);
+
+    // Wait for the supervisor to report the agent PID
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while Instant::now() < deadline {
+        if let Some(state) = load_state(task_id) {
+            if state.supervisor_pid == supervisor_pid {
+                if let Some(error) = state.error {
+                    return Err(anyhow::anyhow!(error));
+                }
+                if state.agent_pid.is_some() {
+                    return Ok(state);
+                }
+            }
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    Err(anyhow::anyhow!("Timed out waiting for the agent supervisor to start"))
+}
+
+/// Entry point for supervisor mode. Returns the process exit code.
+pub fn run(task_id: &str) -> i32 {
+    let spec: SupervisorSpec = match fs::read_to_string(spec_file(task_id))
+        .map_err(anyhow::Error::from)
+        .and_then(|c| serde_json::from_str(&c).map_err(anyhow::Error::from))
+    {
+        Ok(spec) => spec,
+        Err(e) => {
+            error!("Supervisor could not read spec for task {}: {}", task_id, e);
+            return 1;
+        }
+    };
+
+    let mut state = SupervisorState {
+        task_id: task_id.to_string(),
+        supervisor_pid: std::process::id(),
+        agent_pid: None,
+        status: TaskAgentStatus::Starting,
+        exit_code: None,
+        error: None,
+        updated_at: chrono::Utc::now().timestamp(),
+    };
+
+    match supervise(&spec, &mut state) {
+        Ok(()) => 0,
+        Err(e) => {
+            error!("Supervisor for task {} failed: {}", task_id, e);
+            state.status = TaskAgentStatus::Failed;
+            state.error = Some(e.to_string());
+            state.updated_at = chrono::Utc::now().timestamp();
+            save_state(&state);
+            1
+        }
+    }
+}
+
+fn supervise(spec: &SupervisorSpec, state: &mut SupervisorState) -> Result<()> {
+    let task_id = spec.task_id.clone();
+    let backend: Arc<dyn crate::agent_backend::AgentBackend> =
+        backend_for(&spec.backend, spec.agent_command.as_deref())?.into();
+
+    let mut child = backend.spawn(&SpawnRequest {
+        task_id: &task_id,
+        worktree_path: &spec.worktree_path,
+        resume_session_id: spec.resume_session_id.as_deref(),
+        prompt: &spec.prompt,
+        model: spec.model.as_deref(),
+        extra_args: &spec.extra_args,
+    })?;
+
+    state.agent_pid = Some(child.id());
+    state.status = TaskAgentStatus::Running;
+    state.updated_at = chrono::Utc::now().timestamp();
+    save_state(state);
+
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let finished = Arc::new(AtomicBool::new(false));
+
+    // Agent stdout -> session file
+    let stdout_thread = stdout.map(|stdout| {
+        let backend = Arc::clone(&backend);
+        let task_id = task_id.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                for event in backend.parse_output(&line) {
+                    match event {
+                        AgentEvent::SessionStarted(session_id) => save_session_id(&task_id, &session_id),
+                        AgentEvent::Output(line) => append_to_session_file(&task_id, &line),
+                    }
+                }
+            }
+        })
+    });
+
+    // Agent stderr -> stderr file
+    let stderr_thread = stderr.map(|stderr| {
+        let path = stderr_file(&task_id);
+        thread::spawn(move || {
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    let _ = writeln!(file, "{}", line);
+                }
+            }
+        })
+    });
+
+    // Inbox -> agent stdin
+    if let Some(mut stdin) = stdin {
+        let inbox = inbox_file(&task_id);
+        let finished = Arc::clone(&finished);
+        thread::spawn(move || {
+            let mut offset = 0u64;
+            while !finished.load(Ordering::Relaxed) {
+                match read_new_lines(&inbox, &mut offset) {
+                    Ok(lines) => {
+                        for line in lines {
+                            if writeln!(stdin, "{}", line).and_then(|_| stdin.flush()).is_err() {
+                                warn!("Agent stdin closed, stopping inbox forwarding");
+                                return;
+                            }
+                        }
+                    }
+                    Err(e) => warn!("Failed to read inbox: {}", e),
+                }
+                thread::sleep(INBOX_POLL_INTERVAL);
+            }
+        });
+    }
+
+    let exit_status = child.wait();
+    finished.store(true, Ordering::Relaxed);
+
+    // Make sure all output is persisted before reporting completion
+    if let Some(t) = stdout_thread {
+        let _ = t.join();
+    }
+    if let Some(t) = stderr_thread {
+        let _ = t.join();
+    }
+
+    let (status, exit_code) = match exit_status {
+        Ok(s) if s.success() => (TaskAgentStatus::Completed, s.code()),
+        Ok(s) => (TaskAgentStatus::Failed, s.code()),
+        Err(e) => {
+            state.error = Some(e.to_string());
+            (TaskAgentStatus::Failed, None)
+        }
+    };
+
+    state.status = status;
+    state.exit_code = exit_code;
+    state.updated_at = chrono::Utc::now().timestamp();
+    save_state(state);
+    Ok(())
+}
+
+/// Read complete lines appended to a file since `offset`, advancing the offset
+pub fn read_new_lines(path: &Path, offset: &mut u64) -> Result<Vec<String>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    file.seek(SeekFrom::Start(*offset))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+
+    // Only consume up to the last newline; a partial line is picked up next time
+    let Some(last_newline) = buf.iter().rposition(|b| *b == b'\n') else {
+        return Ok(Vec::new());
+    };
+    *offset += (last_newline + 1) as u64;
+
+    Ok(String::from_utf8_lossy(&buf[..last_newline])
+        .lines()
+        .map(|l| l.to_string())
+        .collect())
+}
+
+/// Current length of a file (0 if it does not exist)
+pub fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_new_lines_skips_partial_line() {
+        let path = std::env::temp_dir().join(format!("medusa-inbox-{}", uuid::Uuid::new_v4()));
+        fs::write(&path, "one\ntwo\nthr").unwrap();
+
+        let mut offset = 0;
+        assert_eq!(read_new_lines(&path, &mut offset).unwrap(), vec!["one", "two"]);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "ee").unwrap();
+        assert_eq!(read_new_lines(&path, &mut offset).unwrap(), vec!["three"]);
+        assert!(read_new_lines(&path, &mut offset).unwrap().is_empty());
+
+        fs::remove_file(&path).ok();
+    }
+}
@@ -1,23 +1,26 @@
-//! Task Agent - Manages Claude Code processes for tasks
+//! Task Agent - Manages agent processes for tasks
 //!
-//! This module handles spawning and managing Claude Code CLI processes
-//! that work on tasks in isolated git worktrees.
+//! This module handles spawning and managing coding agent CLI processes
+//! (Claude Code by default, see `agent_backend`) that work on tasks in
+//! isolated git worktrees.
 
+use crate::agent_backend::{AgentBackend, LaunchProfile};
 use crate::git::GitManager;
+use crate::supervisor::{self, SupervisorSpec};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::{Child, ChildStdin, Command, Stdio};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 use tracing::{debug, error, info, warn};
 
 /// Get the directory for storing session files
-fn get_sessions_dir() -> PathBuf {
+pub(crate) fn get_sessions_dir() -> PathBuf {
     let sessions_dir = dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".medusa")
@@ -27,12 +30,12 @@ fn get_sessions_dir() -> PathBuf {
 }
 
 /// Get the session file path for a task
-fn get_session_file(task_id: &str) -> PathBuf {
+pub(crate) fn get_session_file(task_id: &str) -> PathBuf {
     get_sessions_dir().join(format!("{}.jsonl", task_id))
 }
 
 /// Append a line to the session file
-fn append_to_session_file(task_id: &str, line: &str) {
+pub(crate) fn append_to_session_file(task_id: &str, line: &str) {
     let file_path = get_session_file(task_id);
     if let Ok(mut file) = OpenOptions::new()
         .create(true)
@@ -44,7 +47,7 @@ fn append_to_session_file(task_id: &str, line: &str) {
 }
 
 /// Load session from file
-fn load_session_file(task_id: &str) -> Vec<String> {
+pub(crate) fn load_session_file(task_id: &str) -> Vec<String> {
     let file_path = get_session_file(task_id);
     if file_path.exists() {
         if let Ok(content) = fs::read_to_string(&file_path) {
@@ -54,19 +57,31 @@ fn load_session_file(task_id: &str) -> Vec<String> {
     Vec::new()
 }
 
+/// Move a session's history to another agent ID (e.g. when an attempt becomes the task)
+pub(crate) fn move_session_files(from_id: &str, to_id: &str) {
+    let _ = fs::rename(get_session_file(from_id), get_session_file(to_id));
+    let _ = fs::rename(get_session_id_file(from_id), get_session_id_file(to_id));
+}
+
+/// Delete a session's history
+pub(crate) fn remove_session_files(task_id: &str) {
+    let _ = fs::remove_file(get_session_file(task_id));
+    let _ = fs::remove_file(get_session_id_file(task_id));
+}
+
 /// Get the session_id file path for a task
 fn get_session_id_file(task_id: &str) -> PathBuf {
     get_sessions_dir().join(format!("{}.session_id", task_id))
 }
 
 /// Save session_id to file
-fn save_session_id(task_id: &str, session_id: &str) {
+pub(crate) fn save_session_id(task_id: &str, session_id: &str) {
     let file_path = get_session_id_file(task_id);
     let _ = fs::write(&file_path, session_id);
 }
 
 /// Load session_id from file
-fn load_session_id(task_id: &str) -> Option<String> {
+pub(crate) fn load_session_id(task_id: &str) -> Option<String> {
     let file_path = get_session_id_file(task_id);
     if file_path.exists() {
         fs::read_to_string(&file_path).ok().map(|s| s.trim().to_string())
@@ -75,39 +90,164 @@ fn load_session_id(task_id: &str) -> Option<String> {
     }
 }
 
-/// Check if Claude Code CLI is installed and accessible
-pub fn check_claude_cli_installed() -> Result<()> {
-    // Try to find claude in PATH using which/where
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
-
-    // Use zsh with nvm sourced to check for claude
-    let shell_cmd = "export NVM_DIR=\"$HOME/.nvm\"; [ -s \"$NVM_DIR/nvm.sh\" ] && . \"$NVM_DIR/nvm.sh\"; which claude";
-
-    let output = Command::new("/bin/zsh")
-        .args(["-c", shell_cmd])
-        .env("HOME", &home)
-        .output();
-
-    match output {
-        Ok(result) => {
-            if result.status.success() {
-                let path = String::from_utf8_lossy(&result.stdout);
-                info!("Found Claude CLI at: {}", path.trim());
-                Ok(())
-            } else {
-                Err(anyhow::anyhow!(
-                    "Claude Code CLI not found. Please install it first:\n\
-                    npm install -g @anthropic-ai/claude-code\n\n\
-                    Or visit: https://docs.anthropic.com/en/docs/claude-code"
-                ))
+/// Keep an output line in memory and forward it to the frontend
+/// (the supervisor has already persisted it to the session file)
+fn record_output_line(
+    agents: &Arc<Mutex<HashMap<String, TaskAgentProcess>>>,
+    app_handle: &AppHandle,
+    task_id: &str,
+    line: String,
+) {
+    // Store output line in memory (with limit to prevent memory leak)
+    const MAX_OUTPUT_LINES: usize = 10000;
+    const TRIM_AMOUNT: usize = 2000;
+
+    if let Ok(mut agents) = agents.lock() {
+        if let Some(agent) = agents.get_mut(task_id) {
+            agent.info.output_lines.push(line.clone());
+
+            // Trim old lines if we exceed the limit
+            if agent.info.output_lines.len() > MAX_OUTPUT_LINES {
+                agent.info.output_lines.drain(0..TRIM_AMOUNT);
+                debug!("Trimmed {} old output lines for task {}", TRIM_AMOUNT, task_id);
             }
         }
-        Err(e) => {
-            Err(anyhow::anyhow!(
-                "Failed to check for Claude Code CLI: {}. Please ensure it's installed.", e
-            ))
+    }
+
+    // Emit event to frontend
+    let _ = app_handle.emit("agent-output", AgentOutputEvent {
+        task_id: task_id.to_string(),
+        line,
+        is_error: false,
+    });
+}
+
+/// Skip a session file line if the app wrote it itself (user messages)
+fn take_own_line(own_lines: &Arc<Mutex<HashMap<String, Vec<String>>>>, task_id: &str, line: &str) -> bool {
+    if let Ok(mut own_lines) = own_lines.lock() {
+        if let Some(lines) = own_lines.get_mut(task_id) {
+            if let Some(pos) = lines.iter().position(|l| l == line) {
+                lines.remove(pos);
+                return true;
+            }
         }
     }
+    false
+}
+
+/// Where a new task worktree starts from
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartPoint {
+    /// The branch the task merges back into
+    pub base_branch: String,
+    /// Branch or commit the task branch is created from
+    pub start_ref: String,
+}
+
+/// How to start an agent, beyond the task itself
+#[derive(Debug, Clone, Default)]
+pub struct StartOptions {
+    /// Where the worktree starts from (None: the project's current HEAD)
+    pub start_point: Option<StartPoint>,
+    /// Model and extra arguments for the agent CLI
+    pub profile: LaunchProfile,
+}
+
+/// Branch for an agent's worktree: `medusa/task-<id>`, or `medusa/task-<id>-<label>`
+/// for one of several attempts at a task
+pub fn task_branch_name(agent_id: &str) -> String {
+    match crate::attempts::split_agent_id(agent_id) {
+        (task_id, Some(label)) => format!("medusa/task-{}-{}", &task_id[..8], label),
+        (task_id, None) => format!("medusa/task-{}", &task_id[..8]),
+    }
+}
+
+/// Concurrency limits that apply to one project
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AgentLimits {
+    /// Maximum agents running across all projects
+    pub max_total: usize,
+    /// Maximum agents running in this project
+    pub max_per_project: usize,
+}
+
+/// A task waiting for an agent slot
+#[derive(Debug, Clone)]
+pub struct QueuedRun {
+    pub task_id: String,
+    pub project_path: String,
+    pub priority: i64,
+    pub queued_at: u64,
+    /// Prompt to start the agent with
+    pub prompt: String,
+}
+
+/// Pick the queued runs that fit into the free slots: highest priority first, then
+/// oldest first. A project at its limit doesn't block runs of other projects.
+pub fn pick_runnable(
+    queue: &[QueuedRun],
+    running_total: usize,
+    running_per_project: &HashMap<String, usize>,
+    limits_for: impl Fn(&str) -> AgentLimits,
+) -> Vec<QueuedRun> {
+    let mut ordered: Vec<&QueuedRun> = queue.iter().collect();
+    ordered.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.queued_at.cmp(&b.queued_at)));
+
+    let mut total = running_total;
+    let mut per_project = running_per_project.clone();
+    let mut picked = Vec::new();
+
+    for run in ordered {
+        let limits = limits_for(&run.project_path);
+        if total >= limits.max_total {
+            continue;
+        }
+        let in_project = per_project.entry(run.project_path.clone()).or_insert(0);
+        if *in_project >= limits.max_per_project {
+            continue;
+        }
+
+        *in_project += 1;
+        total += 1;
+        picked.push(run.clone());
+    }
+
+    picked
+}
+
+/// Record that an agent exited and notify the frontend
+fn finish_agent(
+    agents: &Arc<Mutex<HashMap<String, TaskAgentProcess>>>,
+    active_sessions: &Arc<Mutex<HashSet<String>>>,
+    own_lines: &Arc<Mutex<HashMap<String, Vec<String>>>>,
+    app_handle: &AppHandle,
+    task_id: &str,
+    new_status: TaskAgentStatus,
+) {
+    // Update agent status (a stopped agent stays Stopped)
+    let mut status = new_status;
+    if let Ok(mut agents) = agents.lock() {
+        if let Some(agent) = agents.get_mut(task_id) {
+            if agent.info.status == TaskAgentStatus::Stopped {
+                status = TaskAgentStatus::Stopped;
+            }
+            agent.info.status = status.clone();
+        }
+    }
+
+    if let Ok(mut sessions) = active_sessions.lock() {
+        sessions.remove(task_id);
+    }
+    if let Ok(mut own_lines) = own_lines.lock() {
+        own_lines.remove(task_id);
+    }
+
+    // Emit status change event
+    let _ = app_handle.emit("agent-status", AgentStatusEvent {
+        task_id: task_id.to_string(),
+        status,
+        message: None,
+    });
 }
 
 /// Validate that a path is a valid git repository
@@ -128,6 +268,7 @@ pub fn validate_git_repository(project_path: &str) -> Result<()> {
 /// Status of a task agent
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskAgentStatus {
+    Queued,
     Starting,
     Running,
     Completed,
@@ -141,6 +282,8 @@ pub struct TaskAgentInfo {
     pub task_id: String,
     pub pid: u32,
     pub status: TaskAgentStatus,
+    pub backend: String, // The agent backend running this task (e.g. "claude")
+    pub project_path: String,
     pub worktree_path: String,
     pub branch: String,
     pub base_commit: String, // The commit the worktree was created from (for accurate diffs)
@@ -166,45 +309,102 @@ pub struct AgentStatusEvent {
 }
 
 /// Manages task agents
+///
+/// Agent processes run under detached supervisors (see `supervisor`), so the
+/// manager only tails their output and forwards messages through the inbox.
 pub struct TaskAgentManager {
     agents: Arc<Mutex<HashMap<String, TaskAgentProcess>>>,
-    /// Store stdin handles for interactive sessions
-    stdin_handles: Arc<Mutex<HashMap<String, ChildStdin>>>,
+    /// Tasks whose agent accepts messages (interactive sessions)
+    active_sessions: Arc<Mutex<HashSet<String>>>,
+    /// Lines the app appended to session files itself, skipped by the output tail
+    own_lines: Arc<Mutex<HashMap<String, Vec<String>>>>,
+    /// Called (from the agent's tail thread) whenever an agent exits and frees a slot
+    slot_freed_hook: Arc<Mutex<Option<SlotFreedHook>>>,
 }
 
+/// Callback run when an agent exits and frees a slot
+pub type SlotFreedHook = fn(AppHandle);
+
 struct TaskAgentProcess {
     info: TaskAgentInfo,
-    #[allow(dead_code)]
-    child: Option<Child>,
+    backend: Arc<dyn AgentBackend>,
 }
 
+/// How often attached agents are polled for new output and exit
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
 impl TaskAgentManager {
     pub fn new() -> Self {
         Self {
             agents: Arc::new(Mutex::new(HashMap::new())),
-            stdin_handles: Arc::new(Mutex::new(HashMap::new())),
+            active_sessions: Arc::new(Mutex::new(HashSet::new())),
+            own_lines: Arc::new(Mutex::new(HashMap::new())),
+            slot_freed_hook: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Register the function that starts queued tasks when an agent exits
+    pub fn on_slot_freed(&self, hook: SlotFreedHook) {
+        if let Ok(mut slot_freed_hook) = self.slot_freed_hook.lock() {
+            *slot_freed_hook = Some(hook);
         }
     }
 
-    /// Start an interactive agent for a task
+    /// Whether another agent may start in this project without exceeding the limits
+    pub fn has_capacity(&self, project_path: &str, limits: &AgentLimits) -> bool {
+        self.free_slots(project_path, limits) > 0
+    }
+
+    /// How many more agents may start in this project without exceeding the limits
+    pub fn free_slots(&self, project_path: &str, limits: &AgentLimits) -> usize {
+        let (total, per_project) = self.running_counts();
+        let in_project = per_project.get(project_path).copied().unwrap_or(0);
+        limits.max_total.saturating_sub(total).min(limits.max_per_project.saturating_sub(in_project))
+    }
+
+    /// Choose which queued runs can start now (see `pick_runnable`)
+    pub fn schedule(&self, queue: &[QueuedRun], limits_for: impl Fn(&str) -> AgentLimits) -> Vec<QueuedRun> {
+        let (total, per_project) = self.running_counts();
+        pick_runnable(queue, total, &per_project, limits_for)
+    }
+
+    /// Agents occupying a slot, in total and per project
+    fn running_counts(&self) -> (usize, HashMap<String, usize>) {
+        let agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        let mut per_project = HashMap::new();
+        let mut total = 0;
+
+        for agent in agents.values() {
+            if matches!(agent.info.status, TaskAgentStatus::Starting | TaskAgentStatus::Running) {
+                *per_project.entry(agent.info.project_path.clone()).or_insert(0) += 1;
+                total += 1;
+            }
+        }
+
+        (total, per_project)
+    }
+
+    /// Start an interactive agent for a task (or one attempt at it, see `attempts`)
     pub fn start_agent(
         &self,
         task_id: &str,
         project_path: &str,
         initial_prompt: &str,
+        backend: Arc<dyn AgentBackend>,
+        options: &StartOptions,
         app_handle: AppHandle,
     ) -> Result<TaskAgentInfo> {
-        info!("Starting interactive agent for task {} in {}", task_id, project_path);
+        info!("Starting interactive {} agent for task {} in {}", backend.id(), task_id, project_path);
 
         // Pre-flight checks
-        // 1. Check if Claude CLI is installed
-        check_claude_cli_installed()?;
+        // 1. Check if the agent CLI is installed
+        backend.check_installed()?;
 
         // 2. Validate git repository
         validate_git_repository(project_path)?;
 
         // Create branch name from task ID
-        let branch_name = format!("medusa/task-{}", &task_id[..8]);
+        let branch_name = task_branch_name(task_id);
 
         // Create git manager and worktree
         let git = GitManager::new(project_path.to_string())?;
@@ -212,11 +412,19 @@ impl TaskAgentManager {
         // IMPORTANT: Capture the current HEAD commit and branch BEFORE creating the worktree
         // base_commit is used for accurate diffs (only shows agent's changes)
         // base_branch is used to merge back to the original branch
-        let base_commit = git.get_current_commit_hash()?;
-        let base_branch = git.current_branch()?;
+        // A start point (from task dependencies) replaces the current HEAD
+        let start_point = options.start_point.as_ref();
+        let (base_commit, base_branch) = match start_point {
+            Some(start_point) => (git.resolve_commit(&start_point.start_ref)?, start_point.base_branch.clone()),
+            None => (git.get_current_commit_hash()?, git.current_branch()?),
+        };
         info!("Base commit for task {}: {} (branch: {})", task_id, base_commit, base_branch);
 
-        let worktree_path = git.create_worktree(task_id, &branch_name)?;
+        let worktree_path = git.create_worktree_from(
+            task_id,
+            &branch_name,
+            start_point.map(|_| base_commit.as_str()),
+        )?;
 
         info!("Created worktree at {:?} on branch {}", worktree_path, branch_name);
 
@@ -229,6 +437,8 @@ impl TaskAgentManager {
             &base_commit,
             &base_branch,
             initial_prompt,
+            backend,
+            &options.profile,
             app_handle,
         );
 
@@ -247,65 +457,51 @@ impl TaskAgentManager {
     fn spawn_agent_process(
         &self,
         task_id: &str,
-        _project_path: &str,
+        project_path: &str,
         worktree_path: &str,
         branch_name: &str,
         base_commit: &str,
         base_branch: &str,
         initial_prompt: &str,
+        backend: Arc<dyn AgentBackend>,
+        profile: &LaunchProfile,
         app_handle: AppHandle,
     ) -> Result<TaskAgentInfo> {
 
         // Check if we have an existing session to resume
-        let existing_session_id = load_session_id(task_id);
-
-        // Build claude args
-        let claude_args = if let Some(ref session_id) = existing_session_id {
-            info!("Resuming session {} for task {}", session_id, task_id);
-            format!(
-                "--resume {} --verbose --output-format stream-json --input-format stream-json --dangerously-skip-permissions",
-                session_id
-            )
+        let existing_session_id = if backend.supports_resume() {
+            load_session_id(task_id)
         } else {
-            "--verbose --output-format stream-json --input-format stream-json --dangerously-skip-permissions".to_string()
+            None
         };
 
-        // Use zsh with nvm sourced explicitly
-        let home = std::env::var("HOME").unwrap_or_else(|_| {
-            // Fallback: try to get home from /etc/passwd or use a default
-            std::env::var("USER")
-                .map(|u| format!("/Users/{}", u))
-                .unwrap_or_else(|_| "/Users".to_string())
-        });
+        // Only stream output produced from now on (earlier lines are loaded from the session file)
+        let session_offset = supervisor::file_len(&get_session_file(task_id));
 
-        let shell_cmd = format!(
-            "export NVM_DIR=\"$HOME/.nvm\"; [ -s \"$NVM_DIR/nvm.sh\" ] && . \"$NVM_DIR/nvm.sh\"; claude {}",
-            claude_args
-        );
-        info!("Running via shell: {}", shell_cmd);
-
-        let mut cmd = Command::new("/bin/zsh");
-        cmd.args(["-c", &shell_cmd])
-            .env("HOME", &home)
-            .env("MEDUSA_TASK_ID", task_id) // Pass task ID so hook script knows this is a task agent
-            .current_dir(&worktree_path)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        // Spawn the process
-        let mut child = cmd.spawn().map_err(|e| {
-            error!("Failed to spawn claude process: {}", e);
-            anyhow::anyhow!("Failed to spawn claude: {}. Is Claude Code CLI installed?", e)
+        // Spawn the agent under a detached supervisor
+        let state = supervisor::launch(&SupervisorSpec {
+            task_id: task_id.to_string(),
+            backend: backend.id().to_string(),
+            agent_command: backend.command().map(String::from),
+            worktree_path: worktree_path.to_string(),
+            resume_session_id: existing_session_id.clone(),
+            prompt: initial_prompt.to_string(),
+            model: profile.model.clone(),
+            extra_args: profile.extra_args.clone(),
+        }).map_err(|e| {
+            error!("Failed to spawn {} process: {}", backend.id(), e);
+            anyhow::anyhow!("Failed to spawn {}: {}. Is the agent CLI installed?", backend.id(), e)
         })?;
 
-        let pid = child.id();
-        info!("Spawned Claude process with PID {} for task {}", pid, task_id);
+        let pid = state.agent_pid.unwrap_or(state.supervisor_pid);
+        info!("Spawned {} process with PID {} for task {}", backend.id(), pid, task_id);
 
         let info = TaskAgentInfo {
             task_id: task_id.to_string(),
             pid,
             status: TaskAgentStatus::Running,
+            backend: backend.id().to_string(),
+            project_path: project_path.to_string(),
             worktree_path: worktree_path.to_string(),
             branch: branch_name.to_string(),
             base_commit: base_commit.to_string(),
@@ -314,239 +510,208 @@ impl TaskAgentManager {
             output_lines: Vec::new(),
         };
 
-        // Take stdin for sending messages
-        let stdin = child.stdin.take();
-        let stdout = child.stdout.take();
-        let stderr = child.stderr.take();
+        // Store agent info
+        {
+            let mut agents = self.agents.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+            agents.insert(task_id.to_string(), TaskAgentProcess {
+                info: info.clone(),
+                backend: Arc::clone(&backend),
+            });
+        }
+        self.attach(task_id, session_offset, 0, app_handle)?;
 
-        // Store stdin handle for later use
-        if let Some(stdin) = stdin {
-            let mut handles = self.stdin_handles.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-            handles.insert(task_id.to_string(), stdin);
+        // Send initial prompt only if this is a new session (not resuming)
+        if existing_session_id.is_none() {
+            if let Some(input) = backend.initial_input(initial_prompt) {
+                self.write_stdin(task_id, &input)?;
+            }
         }
 
-        // Set up stdout streaming
-        let task_id_clone = task_id.to_string();
-        let agents_clone = Arc::clone(&self.agents);
-        let app_handle_stdout = app_handle.clone();
-
-        if let Some(stdout) = stdout {
-            thread::spawn(move || {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    match line {
-                        Ok(line) => {
-                            debug!("Agent {} stdout: {}", task_id_clone, line);
-
-                            // Try to extract session_id from init message
-                            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
-                                if json.get("type").and_then(|t| t.as_str()) == Some("system")
-                                    && json.get("subtype").and_then(|t| t.as_str()) == Some("init")
-                                {
-                                    if let Some(session_id) = json.get("session_id").and_then(|s| s.as_str()) {
-                                        info!("Captured session_id for task {}: {}", task_id_clone, session_id);
-                                        // Save session_id to file for persistence
-                                        save_session_id(&task_id_clone, session_id);
-                                    }
-                                }
-                            }
+        Ok(info)
+    }
+
+    /// Re-attach to an agent left running by a previous app instance.
+    /// `info` describes the task (output lines are loaded from the session file).
+    /// Returns the agent's status: Running if re-attached, Completed or Failed otherwise.
+    pub fn reattach_agent(
+        &self,
+        mut info: TaskAgentInfo,
+        backend: Arc<dyn AgentBackend>,
+        app_handle: AppHandle,
+    ) -> Result<TaskAgentStatus> {
+        let task_id = info.task_id.clone();
+        let state = supervisor::load_state(&task_id);
+
+        info.output_lines = load_session_file(&task_id);
+        info.status = match &state {
+            Some(state) if supervisor::is_live(state) => TaskAgentStatus::Running,
+            Some(state) if state.status == TaskAgentStatus::Completed => TaskAgentStatus::Completed,
+            _ => TaskAgentStatus::Failed,
+        };
+        if let Some(agent_pid) = state.as_ref().and_then(|s| s.agent_pid) {
+            info.pid = agent_pid;
+        }
 
-                            // Persist to session file
-                            append_to_session_file(&task_id_clone, &line);
+        let status = info.status.clone();
+        {
+            let mut agents = self.agents.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+            agents.insert(task_id.clone(), TaskAgentProcess { info, backend });
+        }
 
-                            // Store output line in memory (with limit to prevent memory leak)
-                            const MAX_OUTPUT_LINES: usize = 10000;
-                            const TRIM_AMOUNT: usize = 2000;
+        if status == TaskAgentStatus::Running {
+            let session_offset = supervisor::file_len(&get_session_file(&task_id));
+            let stderr_offset = supervisor::file_len(&supervisor::stderr_file(&task_id));
+            self.attach(&task_id, session_offset, stderr_offset, app_handle)?;
+            info!("Re-attached to running agent for task {}", task_id);
+        } else {
+            info!("Agent for task {} is no longer running ({:?})", task_id, status);
+        }
 
-                            if let Ok(mut agents) = agents_clone.lock() {
-                                if let Some(agent) = agents.get_mut(&task_id_clone) {
-                                    agent.info.output_lines.push(line.clone());
+        Ok(status)
+    }
 
-                                    // Trim old lines if we exceed the limit
-                                    if agent.info.output_lines.len() > MAX_OUTPUT_LINES {
-                                        agent.info.output_lines.drain(0..TRIM_AMOUNT);
-                                        debug!("Trimmed {} old output lines for task {}", TRIM_AMOUNT, task_id_clone);
-                                    }
-                                }
-                            }
+    /// Stream a supervised agent's output to the frontend and watch for its exit
+    fn attach(&self, task_id: &str, session_offset: u64, stderr_offset: u64, app_handle: AppHandle) -> Result<()> {
+        {
+            let mut sessions = self.active_sessions.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+            sessions.insert(task_id.to_string());
+        }
 
-                            // Emit event to frontend
-                            let _ = app_handle_stdout.emit("agent-output", AgentOutputEvent {
-                                task_id: task_id_clone.clone(),
-                                line,
-                                is_error: false,
-                            });
-                        }
-                        Err(e) => {
-                            warn!("Error reading agent stdout: {}", e);
-                            break;
+        let task_id = task_id.to_string();
+        let agents = Arc::clone(&self.agents);
+        let active_sessions = Arc::clone(&self.active_sessions);
+        let own_lines = Arc::clone(&self.own_lines);
+        let slot_freed_hook = Arc::clone(&self.slot_freed_hook);
+
+        thread::spawn(move || {
+            let session_file = get_session_file(&task_id);
+            let stderr_file = supervisor::stderr_file(&task_id);
+            let mut session_offset = session_offset;
+            let mut stderr_offset = stderr_offset;
+
+            loop {
+                // Check for exit first, so output written before exit is still drained below
+                let state = supervisor::load_state(&task_id);
+                let exited = match &state {
+                    Some(state) => !supervisor::is_live(state),
+                    None => true,
+                };
+
+                match supervisor::read_new_lines(&session_file, &mut session_offset) {
+                    Ok(lines) => {
+                        for line in lines {
+                            if take_own_line(&own_lines, &task_id, &line) {
+                                continue;
+                            }
+                            debug!("Agent {} stdout: {}", task_id, line);
+                            record_output_line(&agents, &app_handle, &task_id, line);
                         }
                     }
+                    Err(e) => warn!("Error reading agent output: {}", e),
                 }
-            });
-        }
-
-        // Set up stderr streaming
-        let task_id_clone2 = task_id.to_string();
-        let app_handle_stderr = app_handle.clone();
 
-        if let Some(stderr) = stderr {
-            thread::spawn(move || {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                    match line {
-                        Ok(line) => {
-                            debug!("Agent {} stderr: {}", task_id_clone2, line);
+                match supervisor::read_new_lines(&stderr_file, &mut stderr_offset) {
+                    Ok(lines) => {
+                        for line in lines {
+                            debug!("Agent {} stderr: {}", task_id, line);
 
                             // Emit event to frontend
-                            let _ = app_handle_stderr.emit("agent-output", AgentOutputEvent {
-                                task_id: task_id_clone2.clone(),
+                            let _ = app_handle.emit("agent-output", AgentOutputEvent {
+                                task_id: task_id.clone(),
                                 line,
                                 is_error: true,
                             });
                         }
-                        Err(e) => {
-                            warn!("Error reading agent stderr: {}", e);
-                            break;
-                        }
                     }
+                    Err(e) => warn!("Error reading agent stderr: {}", e),
                 }
-            });
-        }
 
-        // Monitor process completion
-        let task_id_monitor = task_id.to_string();
-        let agents_monitor = Arc::clone(&self.agents);
-        let stdin_handles_monitor = Arc::clone(&self.stdin_handles);
-        let app_handle_monitor = app_handle;
+                if exited {
+                    let new_status = match state.map(|s| s.status) {
+                        Some(TaskAgentStatus::Completed) => {
+                            info!("Agent {} completed successfully", task_id);
+                            TaskAgentStatus::Completed
+                        }
+                        _ => {
+                            warn!("Agent {} failed or its supervisor exited", task_id);
+                            TaskAgentStatus::Failed
+                        }
+                    };
+                    finish_agent(&agents, &active_sessions, &own_lines, &app_handle, &task_id, new_status);
 
-        thread::spawn(move || {
-            // Wait for the child process
-            let status = child.wait();
-
-            let new_status = match status {
-                Ok(exit_status) => {
-                    if exit_status.success() {
-                        info!("Agent {} completed successfully", task_id_monitor);
-                        TaskAgentStatus::Completed
-                    } else {
-                        warn!("Agent {} failed with status {:?}", task_id_monitor, exit_status);
-                        TaskAgentStatus::Failed
+                    // Let the scheduler start the next queued task
+                    let hook = slot_freed_hook.lock().ok().and_then(|h| *h);
+                    if let Some(hook) = hook {
+                        hook(app_handle.clone());
                     }
+                    break;
                 }
-                Err(e) => {
-                    error!("Error waiting for agent {}: {}", task_id_monitor, e);
-                    TaskAgentStatus::Failed
-                }
-            };
 
-            // Update agent status
-            if let Ok(mut agents) = agents_monitor.lock() {
-                if let Some(agent) = agents.get_mut(&task_id_monitor) {
-                    agent.info.status = new_status.clone();
-                }
-            }
-
-            // Remove stdin handle
-            if let Ok(mut handles) = stdin_handles_monitor.lock() {
-                handles.remove(&task_id_monitor);
+                thread::sleep(TAIL_POLL_INTERVAL);
             }
-
-            // Emit status change event
-            let _ = app_handle_monitor.emit("agent-status", AgentStatusEvent {
-                task_id: task_id_monitor,
-                status: new_status,
-                message: None,
-            });
         });
 
-        // Store agent info
-        {
-            let mut agents = self.agents.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-            agents.insert(task_id.to_string(), TaskAgentProcess {
-                info: info.clone(),
-                child: None,
-            });
-        }
-
-        // Send initial prompt only if this is a new session (not resuming)
-        if existing_session_id.is_none() && !initial_prompt.is_empty() {
-            self.send_message(task_id, initial_prompt)?;
-        }
-
-        Ok(info)
+        Ok(())
     }
 
     /// Send a message to a running agent
     pub fn send_message(&self, task_id: &str, message: &str) -> Result<()> {
         info!("Sending message to agent {}: {}", task_id, message);
 
-        let mut handles = self.stdin_handles.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let backend = {
+            let agents = self.agents.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+            agents.get(task_id).map(|a| Arc::clone(&a.backend))
+        }.ok_or_else(|| anyhow::anyhow!("No active session for task {}", task_id))?;
 
-        if let Some(stdin) = handles.get_mut(task_id) {
-            // Format message as JSON for stream-json input format
-            let json_message = serde_json::json!({
-                "type": "user",
-                "message": {
-                    "role": "user",
-                    "content": message
-                }
-            });
+        let encoded = backend.encode_message(message)
+            .ok_or_else(|| anyhow::anyhow!("The {} agent does not accept messages", backend.id()))?;
 
-            let json_str = json_message.to_string();
+        self.write_stdin(task_id, &encoded)?;
 
-            // Persist user message to session file
-            append_to_session_file(task_id, &json_str);
+        info!("Message sent to agent {}", task_id);
+        Ok(())
+    }
 
-            // Also add to in-memory output_lines so it shows in UI immediately
-            if let Ok(mut agents) = self.agents.lock() {
-                if let Some(agent) = agents.get_mut(task_id) {
-                    agent.info.output_lines.push(json_str.clone());
-                }
-            }
+    /// Forward an already-encoded line to the agent's stdin (through the supervisor inbox)
+    fn write_stdin(&self, task_id: &str, line: &str) -> Result<()> {
+        if !self.has_active_session(task_id) {
+            return Err(anyhow::anyhow!("No active session for task {}", task_id));
+        }
 
-            writeln!(stdin, "{}", json_str)?;
-            stdin.flush()?;
+        // Persist user message to session file (the output tail skips lines we wrote)
+        if let Ok(mut own_lines) = self.own_lines.lock() {
+            own_lines.entry(task_id.to_string()).or_default().push(line.to_string());
+        }
+        append_to_session_file(task_id, line);
 
-            info!("Message sent to agent {}", task_id);
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("No active session for task {}", task_id))
+        // Also add to in-memory output_lines so it shows in UI immediately
+        if let Ok(mut agents) = self.agents.lock() {
+            if let Some(agent) = agents.get_mut(task_id) {
+                agent.info.output_lines.push(line.to_string());
+            }
         }
+
+        supervisor::send_input(task_id, line)
     }
 
     /// Stop an agent
     pub fn stop_agent(&self, task_id: &str) -> Result<()> {
         info!("Stopping agent for task {}", task_id);
 
-        // Remove stdin handle first to close the pipe
+        // Stop accepting messages first
         {
-            let mut handles = self.stdin_handles.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-            handles.remove(task_id);
+            let mut sessions = self.active_sessions.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+            sessions.remove(task_id);
         }
 
-        let pid = {
+        let agent = {
             let agents = self.agents.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-            agents.get(task_id).map(|a| a.info.pid)
+            agents.get(task_id).map(|a| (a.info.pid, Arc::clone(&a.backend)))
         };
 
-        if let Some(pid) = pid {
+        if let Some((pid, backend)) = agent {
             // Kill the process
-            #[cfg(unix)]
-            {
-                use std::process::Command;
-                let _ = Command::new("kill")
-                    .args(["-TERM", &pid.to_string()])
-                    .output();
-            }
-
-            #[cfg(windows)]
-            {
-                use std::process::Command;
-                let _ = Command::new("taskkill")
-                    .args(["/PID", &pid.to_string(), "/F"])
-                    .output();
-            }
+            backend.stop(pid)?;
 
             // Update status
             let mut agents = self.agents.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
@@ -568,8 +733,26 @@ impl TaskAgentManager {
 
     /// Check if agent has an active session (can receive messages)
     pub fn has_active_session(&self, task_id: &str) -> bool {
-        let handles = self.stdin_handles.lock().ok();
-        handles.map(|h| h.contains_key(task_id)).unwrap_or(false)
+        let sessions = self.active_sessions.lock().ok();
+        sessions.map(|s| s.contains(task_id)).unwrap_or(false)
+    }
+
+    /// Number of output lines the app holds for an agent (an offset for `turn_ended`)
+    pub fn output_len(&self, task_id: &str) -> usize {
+        let agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        agents.get(task_id).map(|a| a.info.output_lines.len()).unwrap_or(0)
+    }
+
+    /// Whether the agent finished a turn after output line `since`, or isn't running anymore
+    pub fn turn_ended(&self, task_id: &str, since: usize) -> bool {
+        let agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        match agents.get(task_id) {
+            Some(agent) if agent.info.status == TaskAgentStatus::Running => agent.info.output_lines
+                .iter()
+                .skip(since)
+                .any(|line| agent.backend.is_turn_end(line)),
+            _ => true,
+        }
     }
 
     /// List all agents
@@ -592,12 +775,30 @@ impl TaskAgentManager {
         // Remove from tracking
         let mut agents = self.agents.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
         agents.remove(task_id);
+        supervisor::remove_files(task_id);
 
         info!("Cleaned up agent for task {}", task_id);
         Ok(())
     }
 
-    /// Stop all running agents (called on app shutdown)
+    /// Stop tracking an agent without touching its worktree
+    pub fn forget_agent(&self, task_id: &str) {
+        if let Ok(mut sessions) = self.active_sessions.lock() {
+            sessions.remove(task_id);
+        }
+        if let Ok(mut agents) = self.agents.lock() {
+            agents.remove(task_id);
+        }
+        supervisor::remove_files(task_id);
+    }
+
+    /// Number of agents currently running
+    pub fn running_count(&self) -> usize {
+        let agents = self.agents.lock().unwrap_or_else(|e| e.into_inner());
+        agents.values().filter(|a| a.info.status == TaskAgentStatus::Running).count()
+    }
+
+    /// Stop all running agents
     pub fn stop_all_agents(&self) {
         info!("Stopping all running agents...");
 
@@ -651,10 +852,45 @@ lazy_static::lazy_static! {
     pub static ref TASK_AGENT_MANAGER: Arc<Mutex<TaskAgentManager>> = Arc::new(Mutex::new(TaskAgentManager::new()));
 }
 
-/// Stop all agents - called on app shutdown
-pub fn shutdown_all_agents() {
-    info!("App shutdown: stopping all agents");
+/// Detach from all agents - called on app shutdown.
+/// Agents keep running under their supervisors and are re-attached on next start.
+pub fn detach_all_agents() {
     if let Ok(manager) = TASK_AGENT_MANAGER.lock() {
-        manager.stop_all_agents();
+        info!("App shutdown: leaving {} agent(s) running under supervisors", manager.running_count());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(task_id: &str, project_path: &str, priority: i64, queued_at: u64) -> QueuedRun {
+        QueuedRun {
+            task_id: task_id.to_string(),
+            project_path: project_path.to_string(),
+            priority,
+            queued_at,
+            prompt: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_pick_runnable_respects_priority_and_limits() {
+        let queue = vec![
+            run("old", "/a", 0, 1),
+            run("urgent", "/a", 5, 3),
+            run("newer", "/a", 0, 2),
+            run("other", "/b", 0, 4),
+        ];
+        let limits = |_: &str| AgentLimits { max_total: 3, max_per_project: 2 };
+
+        // One agent already runs in /a: /a has one slot left, the global limit two
+        let running = HashMap::from([("/a".to_string(), 1)]);
+        let picked: Vec<String> = pick_runnable(&queue, 1, &running, limits)
+            .into_iter()
+            .map(|r| r.task_id)
+            .collect();
+
+        assert_eq!(picked, vec!["urgent", "other"]);
     }
 }
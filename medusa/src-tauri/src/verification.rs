@@ -0,0 +1,288 @@
+//! Review Verification - Project checks that run before a task enters Review
+//!
+//! A project can configure verification commands (e.g. `cargo test`,
+//! `npm run lint`). When a task is sent to review they run one after another in
+//! the task worktree, each with a timeout, and the outcome is stored with the
+//! task. If the project allows fix retries, a failed run is handed back to the
+//! agent as a follow-up message and the task is re-verified once the agent's
+//! turn ends.
+
+use crate::agent_backend::shell_command;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Most output kept per check (the end of the output is kept)
+const MAX_CHECK_OUTPUT: usize = 64 * 1024;
+
+/// How much of each failed check's output is included in a fix request
+const FIX_PROMPT_OUTPUT: usize = 4 * 1024;
+
+/// How often a running check is polled for exit
+const CHECK_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How long an agent may work on a fix before the task is left for the user
+const FIX_TIMEOUT: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// How often the agent is polled for the end of its fix turn
+const FIX_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Outcome of a verification run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum VerificationStatus {
+    Passed,
+    Failed,
+}
+
+impl VerificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationStatus::Passed => "Passed",
+            VerificationStatus::Failed => "Failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Passed" => Some(VerificationStatus::Passed),
+            "Failed" => Some(VerificationStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Result of one verification command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    /// Combined stdout and stderr (only the end, for very chatty checks)
+    pub output: String,
+}
+
+/// Result of running all of a project's verification commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationResult {
+    pub status: VerificationStatus,
+    pub checks: Vec<CheckResult>,
+    pub started_at: u64,
+    pub finished_at: u64,
+}
+
+impl VerificationResult {
+    pub fn failed_checks(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(|c| !c.passed)
+    }
+}
+
+/// Event emitted when a task's verification starts and finishes
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationEvent {
+    pub task_id: String,
+    /// None while the checks are running
+    pub status: Option<VerificationStatus>,
+}
+
+/// Run every command in the worktree (a failing check doesn't stop the others)
+pub fn run_checks(worktree_path: &Path, commands: &[String], timeout: Duration) -> VerificationResult {
+    let started_at = crate::commands::now();
+    let checks: Vec<CheckResult> = commands
+        .iter()
+        .map(|command| run_check(worktree_path, command, timeout))
+        .collect();
+
+    let status = if checks.iter().all(|c| c.passed) {
+        VerificationStatus::Passed
+    } else {
+        VerificationStatus::Failed
+    };
+
+    VerificationResult {
+        status,
+        checks,
+        started_at,
+        finished_at: crate::commands::now(),
+    }
+}
+
+fn run_check(worktree_path: &Path, command: &str, timeout: Duration) -> CheckResult {
+    info!("Running verification `{}` in {:?}", command, worktree_path);
+    let started = Instant::now();
+
+    let failed = |output: String| CheckResult {
+        command: command.to_string(),
+        passed: false,
+        exit_code: None,
+        timed_out: false,
+        duration_ms: started.elapsed().as_millis() as u64,
+        output,
+    };
+
+    // Interleave stderr with stdout so the log reads like a terminal
+    let mut cmd = shell_command(&format!("( {} ) 2>&1", command));
+    cmd.current_dir(worktree_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    // Own process group, so a timeout kills everything the check started
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return failed(format!("Failed to run check: {}", e)),
+    };
+
+    let output = child.stdout.take().map(|mut stdout| {
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = stdout.read_to_end(&mut output);
+            output
+        })
+    });
+
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {}
+            Err(e) => {
+                warn!("Failed to wait for verification `{}`: {}", command, e);
+                break None;
+            }
+        }
+
+        if started.elapsed() > timeout {
+            timed_out = true;
+            kill_check(&mut child);
+            break child.wait().ok();
+        }
+
+        thread::sleep(CHECK_POLL_INTERVAL);
+    };
+
+    let output = output
+        .and_then(|handle| handle.join().ok())
+        .map(|bytes| tail(&String::from_utf8_lossy(&bytes), MAX_CHECK_OUTPUT).to_string())
+        .unwrap_or_default();
+
+    CheckResult {
+        command: command.to_string(),
+        passed: !timed_out && status.map(|s| s.success()).unwrap_or(false),
+        exit_code: status.and_then(|s| s.code()),
+        timed_out,
+        duration_ms: started.elapsed().as_millis() as u64,
+        output,
+    }
+}
+
+/// Kill a check and anything it spawned
+fn kill_check(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    {
+        let _ = std::process::Command::new("kill")
+            .args(["-KILL", &format!("-{}", child.id())])
+            .status();
+    }
+    let _ = child.kill();
+}
+
+/// The last `max` bytes of `text`, starting on a character boundary
+fn tail(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+/// Follow-up message asking the agent to fix the failed checks
+pub fn fix_prompt(result: &VerificationResult) -> String {
+    let mut prompt = String::from(
+        "The project's verification checks failed on your changes. Fix the problems below, then stop.\n",
+    );
+
+    for check in result.failed_checks() {
+        let outcome = if check.timed_out {
+            "timed out".to_string()
+        } else {
+            match check.exit_code {
+                Some(code) => format!("exited with {}", code),
+                None => "failed".to_string(),
+            }
+        };
+        prompt.push_str(&format!(
+            "\n`{}` {}:\n```\n{}\n```\n",
+            check.command,
+            outcome,
+            tail(check.output.trim_end(), FIX_PROMPT_OUTPUT)
+        ));
+    }
+
+    prompt
+}
+
+/// Wait until the agent finishes the turn started after output line `since`.
+/// Returns false if it's still working when the fix timeout runs out.
+pub fn wait_for_fix(task_id: &str, since: usize) -> bool {
+    let deadline = Instant::now() + FIX_TIMEOUT;
+    while Instant::now() < deadline {
+        let ended = crate::task_agent::TASK_AGENT_MANAGER
+            .lock()
+            .map(|manager| manager.turn_ended(task_id, since))
+            .unwrap_or(true);
+        if ended {
+            return true;
+        }
+        thread::sleep(FIX_POLL_INTERVAL);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix_prompt_lists_failed_checks() {
+        let check = |command: &str, passed: bool, output: &str| CheckResult {
+            command: command.to_string(),
+            passed,
+            exit_code: Some(if passed { 0 } else { 101 }),
+            timed_out: false,
+            duration_ms: 10,
+            output: output.to_string(),
+        };
+        let result = VerificationResult {
+            status: VerificationStatus::Failed,
+            checks: vec![check("cargo fmt --check", true, ""), check("cargo test", false, "test foo ... FAILED\n")],
+            started_at: 0,
+            finished_at: 1,
+        };
+
+        let prompt = fix_prompt(&result);
+        assert!(prompt.contains("`cargo test` exited with 101"));
+        assert!(prompt.contains("test foo ... FAILED"));
+        assert!(!prompt.contains("cargo fmt"));
+    }
+
+    #[test]
+    fn test_tail_keeps_char_boundary() {
+        assert_eq!(tail("héllo", 4), "llo");
+        assert_eq!(tail("abc", 10), "abc");
+    }
+}
@@ -0,0 +1,232 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { ask } from '@tauri-apps/plugin-dialog';
+import { X, GitMerge, Pause, Terminal, Trash2, FileCode, DollarSign, Loader2 } from 'lucide-react';
+import { Task, AttemptComparison } from '../../types';
+import { AgentOutputModal } from './AgentOutputModal';
+
+interface CompareAttemptsModalProps {
+  task: Task;
+  onClose: () => void;
+  onChanged: () => void;
+}
+
+export function CompareAttemptsModal({ task, onClose, onChanged }: CompareAttemptsModalProps) {
+  const [comparisons, setComparisons] = useState<AttemptComparison[]>([]);
+  const [outputAttempt, setOutputAttempt] = useState<AttemptComparison | null>(null);
+  const [busyId, setBusyId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadComparisons = useCallback(async () => {
+    try {
+      setComparisons(await invoke<AttemptComparison[]>('compare_task_attempts', { taskId: task.id }));
+    } catch (err) {
+      setError(String(err));
+    }
+  }, [task.id]);
+
+  useEffect(() => {
+    loadComparisons();
+    const interval = setInterval(loadComparisons, 5000);
+    return () => clearInterval(interval);
+  }, [loadComparisons]);
+
+  // Refresh when an attempt's agent changes state
+  useEffect(() => {
+    let isMounted = true;
+    let unlistenFn: (() => void) | null = null;
+
+    listen('agent-status', () => {
+      if (!isMounted) return;
+      loadComparisons();
+    }).then((fn) => {
+      unlistenFn = fn;
+      if (!isMounted) fn();
+    });
+
+    return () => {
+      isMounted = false;
+      if (unlistenFn) unlistenFn();
+    };
+  }, [loadComparisons]);
+
+  // Handle ESC key to close
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'Escape' && !outputAttempt) {
+        onClose();
+      }
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, [onClose, outputAttempt]);
+
+  const runAction = async (attemptId: string, action: () => Promise<unknown>) => {
+    setBusyId(attemptId);
+    setError(null);
+    try {
+      await action();
+      onChanged();
+      await loadComparisons();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setBusyId(null);
+    }
+  };
+
+  const handleMerge = async (comparison: AttemptComparison) => {
+    const confirmed = await ask(
+      `Merge attempt ${comparison.attempt.label} and discard the other attempts?`,
+      { title: 'Merge Attempt', kind: 'warning' }
+    );
+    if (!confirmed) return;
+
+    await runAction(comparison.attempt.id, () =>
+      invoke('merge_task_attempt', { taskId: task.id, attemptId: comparison.attempt.id })
+    );
+    onClose();
+  };
+
+  const handleDiscard = async (comparison: AttemptComparison) => {
+    const confirmed = await ask(`Discard attempt ${comparison.attempt.label} and its changes?`, {
+      title: 'Discard Attempt',
+      kind: 'warning',
+    });
+    if (!confirmed) return;
+
+    await runAction(comparison.attempt.id, () =>
+      invoke('discard_task_attempt', { taskId: task.id, attemptId: comparison.attempt.id })
+    );
+    if (comparisons.length === 1) onClose();
+  };
+
+  const describeProfile = (comparison: AttemptComparison) => {
+    const { profile, backend } = comparison.attempt;
+    return [backend, profile.model, ...profile.extra_args].filter(Boolean).join(' · ');
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm">
+      <div className="w-full max-w-5xl max-h-[90vh] m-4 bg-background rounded-xl shadow-2xl border border-border flex flex-col overflow-hidden">
+        {/* Header */}
+        <header className="flex items-center justify-between px-6 py-4 border-b border-border shrink-0">
+          <div>
+            <h2 className="text-lg font-semibold text-foreground">Compare Attempts</h2>
+            <p className="text-xs text-muted-foreground truncate max-w-xl">{task.title}</p>
+          </div>
+          <button
+            onClick={onClose}
+            className="p-2 text-muted-foreground hover:text-foreground hover:bg-muted rounded-lg transition-colors"
+            title="Close (ESC)"
+          >
+            <X className="w-5 h-5" />
+          </button>
+        </header>
+
+        {/* Attempts side by side */}
+        <div className="flex-1 overflow-auto p-6">
+          {error && <p className="text-sm text-red-500 mb-4">{error}</p>}
+
+          <div className="flex gap-4">
+            {comparisons.map((comparison) => {
+              const { attempt, diff } = comparison;
+              const isRunning = comparison.status === 'Running' || comparison.status === 'Starting';
+              const isBusy = busyId === attempt.id;
+
+              return (
+                <div key={attempt.id} className="flex-1 min-w-[220px] border border-border rounded-lg p-4 space-y-3">
+                  <div className="flex items-center justify-between">
+                    <span className="text-sm font-semibold text-foreground">Attempt {attempt.label}</span>
+                    {isRunning ? (
+                      <span className="flex items-center gap-1 text-xs text-muted-foreground">
+                        <span className="w-2 h-2 bg-green-500 rounded-full animate-pulse" />
+                        Running
+                      </span>
+                    ) : (
+                      <span className="text-xs text-muted-foreground">{comparison.status ?? 'Stopped'}</span>
+                    )}
+                  </div>
+
+                  <p className="text-xs text-muted-foreground font-mono truncate" title={describeProfile(comparison)}>
+                    {describeProfile(comparison)}
+                  </p>
+
+                  <div className="flex items-center gap-3 text-xs">
+                    <span className="flex items-center gap-1 text-muted-foreground">
+                      <FileCode className="w-3 h-3" />
+                      {diff.files_changed} file{diff.files_changed !== 1 ? 's' : ''}
+                    </span>
+                    <span className="text-green-600">+{diff.insertions}</span>
+                    <span className="text-red-500">-{diff.deletions}</span>
+                    {comparison.cost_usd !== undefined && comparison.cost_usd !== null && (
+                      <span className="flex items-center text-muted-foreground">
+                        <DollarSign className="w-3 h-3" />
+                        {comparison.cost_usd.toFixed(2)}
+                      </span>
+                    )}
+                  </div>
+
+                  <div className="space-y-1 max-h-40 overflow-auto">
+                    {comparison.changed_files.map((file) => (
+                      <div key={file} className="text-xs text-muted-foreground font-mono py-1 px-2 bg-muted/30 rounded truncate">
+                        {file}
+                      </div>
+                    ))}
+                  </div>
+
+                  <div className="flex items-center gap-1 pt-2 border-t border-border">
+                    <button
+                      onClick={() => setOutputAttempt(comparison)}
+                      className="p-1.5 text-muted-foreground hover:text-primary hover:bg-primary/10 rounded transition-colors"
+                      title="View Output"
+                    >
+                      <Terminal className="w-3.5 h-3.5" />
+                    </button>
+                    {isRunning && (
+                      <button
+                        onClick={() => runAction(attempt.id, () => invoke('stop_task_attempt', { attemptId: attempt.id }))}
+                        className="p-1.5 text-muted-foreground hover:text-muted-foreground/80 hover:bg-muted rounded transition-colors"
+                        title="Stop Agent"
+                      >
+                        <Pause className="w-3.5 h-3.5" />
+                      </button>
+                    )}
+                    <button
+                      onClick={() => handleDiscard(comparison)}
+                      className="p-1.5 text-muted-foreground hover:text-red-500 hover:bg-red-500/10 rounded transition-colors"
+                      title="Discard Attempt"
+                    >
+                      <Trash2 className="w-3.5 h-3.5" />
+                    </button>
+                    <button
+                      onClick={() => handleMerge(comparison)}
+                      disabled={isBusy || diff.files_changed === 0}
+                      className="ml-auto flex items-center gap-1.5 px-3 py-1.5 text-xs font-medium bg-primary/10 text-primary rounded-lg hover:bg-primary/20 disabled:opacity-50 disabled:cursor-not-allowed transition-colors"
+                    >
+                      {isBusy ? <Loader2 className="w-3 h-3 animate-spin" /> : <GitMerge className="w-3 h-3" />}
+                      Merge
+                    </button>
+                  </div>
+                </div>
+              );
+            })}
+          </div>
+        </div>
+      </div>
+
+      {outputAttempt && (
+        <AgentOutputModal
+          task={{
+            ...task,
+            id: outputAttempt.attempt.id,
+            title: `${task.title} (attempt ${outputAttempt.attempt.label})`,
+            agent_pid: outputAttempt.attempt.agent_pid,
+          }}
+          onClose={() => setOutputAttempt(null)}
+        />
+      )}
+    </div>
+  );
+}
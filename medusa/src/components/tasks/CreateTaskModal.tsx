@@ -1,16 +1,36 @@
 import { useState, useEffect } from 'react';
 import { X, FolderOpen } from 'lucide-react';
 import { open } from '@tauri-apps/plugin-dialog';
+import { Task, TaskStatus } from '../../types';
+
+export interface NewTaskOptions {
+  priority: number;
+  depends_on: string[];
+  branch_from_dependency: boolean;
+}
 
 interface CreateTaskModalProps {
   onClose: () => void;
-  onCreate: (title: string, description: string, projectPath: string) => void;
+  onCreate: (title: string, description: string, projectPath: string, options: NewTaskOptions) => void;
+  tasks?: Task[]; // Existing tasks, offered as prerequisites
 }
 
-export function CreateTaskModal({ onClose, onCreate }: CreateTaskModalProps) {
+export function CreateTaskModal({ onClose, onCreate, tasks = [] }: CreateTaskModalProps) {
   const [title, setTitle] = useState('');
   const [description, setDescription] = useState('');
   const [projectPath, setProjectPath] = useState('');
+  const [priority, setPriority] = useState(0);
+  const [dependsOn, setDependsOn] = useState<string[]>([]);
+  const [branchFromDependency, setBranchFromDependency] = useState(false);
+
+  // Prerequisites must be unfinished tasks of the same project
+  const prerequisiteCandidates = tasks.filter(
+    (t) => t.project_path === projectPath.trim() && t.status !== TaskStatus.Done
+  );
+
+  const toggleDependency = (id: string) => {
+    setDependsOn((prev) => (prev.includes(id) ? prev.filter((d) => d !== id) : [...prev, id]));
+  };
 
   // Handle ESC key to close
   useEffect(() => {
@@ -37,7 +57,11 @@ export function CreateTaskModal({ onClose, onCreate }: CreateTaskModalProps) {
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
     if (title.trim() && projectPath.trim()) {
-      onCreate(title.trim(), description.trim(), projectPath.trim());
+      onCreate(title.trim(), description.trim(), projectPath.trim(), {
+        priority,
+        depends_on: dependsOn.filter((id) => prerequisiteCandidates.some((t) => t.id === id)),
+        branch_from_dependency: branchFromDependency,
+      });
     }
   };
 
@@ -121,6 +145,56 @@ export function CreateTaskModal({ onClose, onCreate }: CreateTaskModalProps) {
             )}
           </div>
 
+          {/* Priority */}
+          <div className="space-y-2">
+            <label htmlFor="priority" className="text-sm font-medium text-foreground">
+              Priority
+            </label>
+            <select
+              id="priority"
+              value={priority}
+              onChange={(e) => setPriority(Number(e.target.value))}
+              className="w-full px-3 py-2 text-sm bg-muted border border-border rounded-lg focus:outline-none focus:border-primary"
+            >
+              <option value={-1}>Low</option>
+              <option value={0}>Normal</option>
+              <option value={1}>High</option>
+              <option value={2}>Urgent</option>
+            </select>
+            <p className="text-xs text-muted-foreground">
+              When all agent slots are busy, higher priority tasks start first
+            </p>
+          </div>
+
+          {/* Dependencies */}
+          {prerequisiteCandidates.length > 0 && (
+            <div className="space-y-2">
+              <label className="text-sm font-medium text-foreground">Depends On</label>
+              <div className="max-h-32 overflow-y-auto space-y-1 px-3 py-2 bg-muted border border-border rounded-lg">
+                {prerequisiteCandidates.map((t) => (
+                  <label key={t.id} className="flex items-center gap-2 text-sm text-foreground cursor-pointer">
+                    <input
+                      type="checkbox"
+                      checked={dependsOn.includes(t.id)}
+                      onChange={() => toggleDependency(t.id)}
+                    />
+                    <span className="truncate">{t.title}</span>
+                  </label>
+                ))}
+              </div>
+              {dependsOn.length > 0 && (
+                <label className="flex items-center gap-2 text-xs text-muted-foreground cursor-pointer">
+                  <input
+                    type="checkbox"
+                    checked={branchFromDependency}
+                    onChange={(e) => setBranchFromDependency(e.target.checked)}
+                  />
+                  Start from the prerequisite's branch once it's in review (don't wait for the merge)
+                </label>
+              )}
+            </div>
+          )}
+
           {/* Actions */}
           <div className="flex justify-end gap-3 pt-4">
             <button
@@ -0,0 +1,249 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { X, Split, Loader2, Plus, Trash2, RefreshCw } from 'lucide-react';
+import { Task, SubtaskProposal, TaskDecomposition } from '../../types';
+
+interface DecomposeTaskModalProps {
+  task: Task;
+  onClose: () => void;
+  onCreated: () => void;
+}
+
+export function DecomposeTaskModal({ task, onClose, onCreated }: DecomposeTaskModalProps) {
+  const [decomposition, setDecomposition] = useState<TaskDecomposition | null>(null);
+  const [subtasks, setSubtasks] = useState<SubtaskProposal[]>([]);
+  const [creating, setCreating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const applyDecomposition = (result: TaskDecomposition | null) => {
+    setDecomposition(result);
+    if (result?.status === 'Ready') {
+      setSubtasks(result.subtasks);
+    }
+  };
+
+  // Load an existing breakdown, or ask the agent for one
+  useEffect(() => {
+    invoke<TaskDecomposition | null>('get_task_decomposition', { taskId: task.id })
+      .then((existing) => {
+        if (existing) {
+          applyDecomposition(existing);
+        } else {
+          return invoke('decompose_task', { taskId: task.id });
+        }
+      })
+      .catch((err) => setError(String(err)));
+  }, [task.id]);
+
+  // Listen for the breakdown to arrive
+  useEffect(() => {
+    let isMounted = true;
+    let unlistenFn: (() => void) | null = null;
+
+    listen<TaskDecomposition>('task-decomposition', (event) => {
+      if (!isMounted || event.payload.task_id !== task.id) return;
+      applyDecomposition(event.payload);
+    }).then((fn) => {
+      unlistenFn = fn;
+      if (!isMounted) fn();
+    });
+
+    return () => {
+      isMounted = false;
+      if (unlistenFn) unlistenFn();
+    };
+  }, [task.id]);
+
+  // Handle ESC key to close
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'Escape') {
+        onClose();
+      }
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, [onClose]);
+
+  const handleRetry = async () => {
+    setError(null);
+    setSubtasks([]);
+    try {
+      await invoke('decompose_task', { taskId: task.id });
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const updateSubtask = (index: number, changes: Partial<SubtaskProposal>) => {
+    setSubtasks(subtasks.map((s, i) => (i === index ? { ...s, ...changes } : s)));
+  };
+
+  // Removing a subtask shifts the indexes the others depend on
+  const removeSubtask = (index: number) => {
+    setSubtasks(
+      subtasks
+        .filter((_, i) => i !== index)
+        .map((s) => ({
+          ...s,
+          depends_on: s.depends_on.filter((d) => d !== index).map((d) => (d > index ? d - 1 : d)),
+        }))
+    );
+  };
+
+  const toggleDependency = (index: number, dependency: number) => {
+    const dependsOn = subtasks[index].depends_on;
+    updateSubtask(index, {
+      depends_on: dependsOn.includes(dependency)
+        ? dependsOn.filter((d) => d !== dependency)
+        : [...dependsOn, dependency],
+    });
+  };
+
+  const handleCreate = async () => {
+    setCreating(true);
+    setError(null);
+    try {
+      await invoke('create_subtasks', { parentId: task.id, subtasks });
+      onCreated();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const isRunning = !decomposition || decomposition.status === 'Running';
+  const isValid = subtasks.length > 0 && subtasks.every((s) => s.title.trim());
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm">
+      <div className="w-full max-w-2xl max-h-[90vh] m-4 bg-background rounded-xl shadow-2xl border border-border flex flex-col overflow-hidden">
+        {/* Header */}
+        <header className="flex items-center justify-between px-6 py-4 border-b border-border shrink-0">
+          <div className="flex items-center gap-3">
+            <div className="w-10 h-10 rounded-full bg-primary/10 flex items-center justify-center">
+              <Split className="w-5 h-5 text-primary" />
+            </div>
+            <div>
+              <h2 className="text-lg font-semibold text-foreground">Split into Subtasks</h2>
+              <p className="text-xs text-muted-foreground truncate max-w-md">{task.title}</p>
+            </div>
+          </div>
+          <button
+            onClick={onClose}
+            className="p-2 text-muted-foreground hover:text-foreground hover:bg-muted rounded-lg transition-colors"
+            title="Close (ESC)"
+          >
+            <X className="w-5 h-5" />
+          </button>
+        </header>
+
+        {/* Main Content */}
+        <div className="flex-1 overflow-auto p-6 space-y-4">
+          {isRunning && !error && (
+            <div className="flex flex-col items-center gap-3 py-10 text-center">
+              <Loader2 className="w-6 h-6 text-primary animate-spin" />
+              <p className="text-sm text-muted-foreground">
+                The agent is exploring the project and proposing a breakdown...
+              </p>
+            </div>
+          )}
+
+          {decomposition?.status === 'Failed' && (
+            <div className="space-y-3">
+              <p className="text-sm text-red-500">{decomposition.error}</p>
+              {decomposition.raw_output && (
+                <pre className="text-xs text-muted-foreground bg-muted/50 rounded-lg p-3 whitespace-pre-wrap max-h-64 overflow-auto">
+                  {decomposition.raw_output}
+                </pre>
+              )}
+            </div>
+          )}
+
+          {decomposition?.status === 'Ready' && subtasks.map((subtask, index) => (
+            <div key={index} className="border border-border rounded-lg p-3 space-y-2">
+              <div className="flex items-center gap-2">
+                <span className="text-xs text-muted-foreground font-mono w-5 shrink-0">{index + 1}.</span>
+                <input
+                  type="text"
+                  value={subtask.title}
+                  onChange={(e) => updateSubtask(index, { title: e.target.value })}
+                  placeholder="Subtask title"
+                  className="flex-1 px-3 py-1.5 text-sm bg-muted border border-border rounded-lg focus:outline-none focus:border-primary"
+                />
+                <button
+                  onClick={() => removeSubtask(index)}
+                  className="p-1.5 text-muted-foreground hover:text-red-500 hover:bg-red-500/10 rounded transition-colors"
+                  title="Remove"
+                >
+                  <Trash2 className="w-3.5 h-3.5" />
+                </button>
+              </div>
+              <textarea
+                value={subtask.description}
+                onChange={(e) => updateSubtask(index, { description: e.target.value })}
+                rows={2}
+                className="w-full px-3 py-2 text-sm bg-muted border border-border rounded-lg focus:outline-none focus:border-primary resize-none"
+              />
+              {index > 0 && (
+                <div className="flex flex-wrap items-center gap-2 text-xs text-muted-foreground">
+                  <span>Depends on:</span>
+                  {subtasks.slice(0, index).map((_, dependency) => (
+                    <label key={dependency} className="flex items-center gap-1">
+                      <input
+                        type="checkbox"
+                        checked={subtask.depends_on.includes(dependency)}
+                        onChange={() => toggleDependency(index, dependency)}
+                      />
+                      {dependency + 1}
+                    </label>
+                  ))}
+                </div>
+              )}
+            </div>
+          ))}
+
+          {decomposition?.status === 'Ready' && (
+            <button
+              onClick={() => setSubtasks([...subtasks, { title: '', description: '', depends_on: [] }])}
+              className="flex items-center gap-1.5 text-xs font-medium text-primary hover:opacity-80"
+            >
+              <Plus className="w-3.5 h-3.5" />
+              Add Subtask
+            </button>
+          )}
+
+          {error && <p className="text-sm text-red-500">{error}</p>}
+        </div>
+
+        {/* Actions */}
+        <footer className="flex justify-end gap-3 px-6 py-4 border-t border-border shrink-0">
+          {!isRunning && (
+            <button
+              onClick={handleRetry}
+              className="flex items-center gap-1.5 px-4 py-2 text-sm font-medium text-muted-foreground hover:text-foreground transition-colors"
+            >
+              <RefreshCw className="w-3.5 h-3.5" />
+              Propose Again
+            </button>
+          )}
+          <button
+            onClick={onClose}
+            className="px-4 py-2 text-sm font-medium text-muted-foreground hover:text-foreground transition-colors"
+          >
+            Cancel
+          </button>
+          <button
+            onClick={handleCreate}
+            disabled={!isValid || creating}
+            className="px-4 py-2 text-sm font-medium bg-primary text-primary-foreground rounded-lg hover:opacity-90 disabled:opacity-50 disabled:cursor-not-allowed transition-opacity"
+          >
+            {creating ? 'Creating...' : `Create ${subtasks.length} Subtask${subtasks.length !== 1 ? 's' : ''}`}
+          </button>
+        </footer>
+      </div>
+    </div>
+  );
+}
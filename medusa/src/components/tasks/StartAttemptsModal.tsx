@@ -0,0 +1,154 @@
+import { useState, useEffect } from 'react';
+import { X, Plus, Trash2 } from 'lucide-react';
+import { Task, LaunchProfile } from '../../types';
+
+const MAX_ATTEMPTS = 8;
+
+interface AttemptDraft {
+  backend: string; // '' uses the task's or the default backend
+  model: string;
+  extraArgs: string;
+}
+
+interface StartAttemptsModalProps {
+  task: Task;
+  onClose: () => void;
+  onStart: (profiles: LaunchProfile[]) => void;
+}
+
+export function StartAttemptsModal({ task, onClose, onStart }: StartAttemptsModalProps) {
+  const [drafts, setDrafts] = useState<AttemptDraft[]>([
+    { backend: '', model: '', extraArgs: '' },
+    { backend: '', model: '', extraArgs: '' },
+  ]);
+
+  // Handle ESC key to close
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'Escape') {
+        onClose();
+      }
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, [onClose]);
+
+  const updateDraft = (index: number, changes: Partial<AttemptDraft>) => {
+    setDrafts(drafts.map((d, i) => (i === index ? { ...d, ...changes } : d)));
+  };
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    onStart(
+      drafts.map((d) => ({
+        backend: d.backend || undefined,
+        model: d.model.trim() || undefined,
+        extra_args: d.extraArgs.split(/\s+/).filter(Boolean),
+      }))
+    );
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm">
+      <div className="w-full max-w-lg bg-background rounded-xl shadow-2xl border border-border overflow-hidden">
+        {/* Header */}
+        <header className="flex items-center justify-between px-6 py-4 border-b border-border">
+          <div>
+            <h2 className="text-lg font-semibold text-foreground">Run Parallel Attempts</h2>
+            <p className="text-xs text-muted-foreground truncate max-w-sm">{task.title}</p>
+          </div>
+          <button
+            onClick={onClose}
+            className="p-2 text-muted-foreground hover:text-foreground hover:bg-muted rounded-lg transition-colors"
+            title="Close (ESC)"
+          >
+            <X className="w-5 h-5" />
+          </button>
+        </header>
+
+        {/* Form */}
+        <form onSubmit={handleSubmit} className="p-6 space-y-4">
+          <p className="text-xs text-muted-foreground">
+            Each attempt runs its own agent in its own worktree and branch. Compare them when they finish and merge
+            the best one; the others are discarded.
+          </p>
+
+          <div className="space-y-3 max-h-80 overflow-auto">
+            {drafts.map((draft, index) => (
+              <div key={index} className="border border-border rounded-lg p-3 space-y-2">
+                <div className="flex items-center justify-between">
+                  <span className="text-sm font-medium text-foreground">
+                    Attempt {String.fromCharCode(97 + index)}
+                  </span>
+                  {drafts.length > 2 && (
+                    <button
+                      type="button"
+                      onClick={() => setDrafts(drafts.filter((_, i) => i !== index))}
+                      className="p-1.5 text-muted-foreground hover:text-red-500 hover:bg-red-500/10 rounded transition-colors"
+                      title="Remove"
+                    >
+                      <Trash2 className="w-3.5 h-3.5" />
+                    </button>
+                  )}
+                </div>
+                <div className="flex gap-2">
+                  <select
+                    value={draft.backend}
+                    onChange={(e) => updateDraft(index, { backend: e.target.value })}
+                    className="px-3 py-2 text-sm bg-muted border border-border rounded-lg focus:outline-none focus:border-primary"
+                  >
+                    <option value="">Default backend</option>
+                    <option value="claude">Claude Code</option>
+                    <option value="command">Custom command</option>
+                  </select>
+                  <input
+                    type="text"
+                    value={draft.model}
+                    onChange={(e) => updateDraft(index, { model: e.target.value })}
+                    placeholder="Model (optional)"
+                    className="flex-1 px-3 py-2 text-sm bg-muted border border-border rounded-lg focus:outline-none focus:border-primary"
+                  />
+                </div>
+                <input
+                  type="text"
+                  value={draft.extraArgs}
+                  onChange={(e) => updateDraft(index, { extraArgs: e.target.value })}
+                  placeholder="Extra CLI arguments (optional)"
+                  className="w-full px-3 py-2 text-sm font-mono bg-muted border border-border rounded-lg focus:outline-none focus:border-primary"
+                />
+              </div>
+            ))}
+          </div>
+
+          {drafts.length < MAX_ATTEMPTS && (
+            <button
+              type="button"
+              onClick={() => setDrafts([...drafts, { backend: '', model: '', extraArgs: '' }])}
+              className="flex items-center gap-1.5 text-xs font-medium text-primary hover:opacity-80"
+            >
+              <Plus className="w-3.5 h-3.5" />
+              Add Attempt
+            </button>
+          )}
+
+          {/* Actions */}
+          <div className="flex justify-end gap-3 pt-4">
+            <button
+              type="button"
+              onClick={onClose}
+              className="px-4 py-2 text-sm font-medium text-muted-foreground hover:text-foreground transition-colors"
+            >
+              Cancel
+            </button>
+            <button
+              type="submit"
+              className="px-4 py-2 text-sm font-medium bg-primary text-primary-foreground rounded-lg hover:opacity-90 transition-opacity"
+            >
+              Start {drafts.length} Attempts
+            </button>
+          </div>
+        </form>
+      </div>
+    </div>
+  );
+}
@@ -55,6 +55,8 @@ export function TaskCard({
     switch (status) {
       case TaskStatus.Backlog:
         return 'bg-muted text-muted-foreground';
+      case TaskStatus.Queued:
+        return 'bg-muted text-muted-foreground';
       case TaskStatus.InProgress:
         return 'bg-primary/10 text-primary';
       case TaskStatus.Review:
@@ -72,6 +74,7 @@ export function TaskCard({
   const isInProgressNotRunning = task.status === TaskStatus.InProgress && !task.agent_pid;
   const canStart = task.status === TaskStatus.Backlog;
   const canResume = isPaused;
+  const isQueued = task.status === TaskStatus.Queued;
 
   return (
     <div
@@ -98,6 +101,11 @@ export function TaskCard({
             <Pause className="w-3 h-3" />
             Paused
           </span>
+        ) : isQueued ? (
+          <span className="flex items-center gap-1 text-xs text-muted-foreground">
+            <Clock className="w-3 h-3" />
+            Queued
+          </span>
         ) : null}
       </div>
 
@@ -165,15 +173,15 @@ export function TaskCard({
             </button>
           )}
 
-          {/* Pause Agent button - shown when running */}
-          {isRunning && onStopAgent && (
+          {/* Pause Agent button - shown when running (or leaves the queue when queued) */}
+          {(isRunning || isQueued) && onStopAgent && (
             <button
               onClick={(e) => {
                 e.stopPropagation();
                 onStopAgent();
               }}
               className="p-1.5 text-muted-foreground hover:text-muted-foreground/80 hover:bg-muted rounded transition-colors"
-              title="Pause Agent"
+              title={isQueued ? 'Remove from Queue' : 'Pause Agent'}
             >
               <Pause className="w-3.5 h-3.5" />
             </button>
@@ -1,4 +1,8 @@
 export { TaskCard } from './TaskCard';
 export { CreateTaskModal } from './CreateTaskModal';
+export type { NewTaskOptions } from './CreateTaskModal';
 export { AgentOutputModal } from './AgentOutputModal';
 export { TaskPreviewModal } from './TaskPreviewModal';
+export { DecomposeTaskModal } from './DecomposeTaskModal';
+export { StartAttemptsModal } from './StartAttemptsModal';
+export { CompareAttemptsModal } from './CompareAttemptsModal';
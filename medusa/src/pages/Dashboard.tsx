@@ -109,6 +109,7 @@ export default function Dashboard() {
     if (isRunning) return 'agent running';
     switch (status) {
       case TaskStatus.Backlog: return 'in backlog';
+      case TaskStatus.Queued: return 'queued';
       case TaskStatus.InProgress: return 'in progress';
       case TaskStatus.Review: return 'ready for review';
       case TaskStatus.Done: return 'completed';
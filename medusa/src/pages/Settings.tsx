@@ -8,7 +8,7 @@ import {
   DropdownMenuItem,
   DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
-import { ChevronDown, Check, ArrowLeft, Moon, Sun, Monitor, Type, RotateCcw, CheckCircle, XCircle, RefreshCw, Loader2, Clock } from "lucide-react";
+import { ChevronDown, Check, ArrowLeft, Moon, Sun, Monitor, Type, RotateCcw, CheckCircle, XCircle, RefreshCw, Loader2, Clock, Cpu } from "lucide-react";
 import { useTheme } from "@/contexts/ThemeContext";
 import { useFontSettings } from "@/contexts/FontContext";
 import { SetupStatus, MedusaSettings } from "@/types";
@@ -20,7 +20,12 @@ const Settings = () => {
   const [setupStatus, setSetupStatus] = useState<SetupStatus | null>(null);
   const [reinstalling, setReinstalling] = useState(false);
   const [appVersion, setAppVersion] = useState<string>("");
-  const [medusaSettings, setMedusaSettings] = useState<MedusaSettings>({ hook_timeout_minutes: 10 });
+  const [medusaSettings, setMedusaSettings] = useState<MedusaSettings>({
+    hook_timeout_minutes: 10,
+    agent_backend: 'claude',
+    max_concurrent_agents: 3,
+    project_agent_limits: {},
+  });
   const [savingSettings, setSavingSettings] = useState(false);
 
   useEffect(() => {
@@ -77,6 +82,20 @@ const Settings = () => {
     saveMedusaSettings(newSettings);
   };
 
+  const handleMaxAgentsChange = (count: number) => {
+    const newSettings = { ...medusaSettings, max_concurrent_agents: count };
+    saveMedusaSettings(newSettings);
+  };
+
+  const handleMaxAgentsPerProjectChange = (count: number) => {
+    // The highest value means "no per-project limit"
+    const newSettings = {
+      ...medusaSettings,
+      max_agents_per_project: count >= medusaSettings.max_concurrent_agents ? undefined : count,
+    };
+    saveMedusaSettings(newSettings);
+  };
+
   const handleReinstall = async () => {
     setReinstalling(true);
     try {
@@ -363,6 +382,95 @@ const Settings = () => {
             </div>
           </div>
 
+          {/* Agents Section */}
+          <div className="space-y-4">
+            <div>
+              <h2 className="text-sm font-medium text-foreground">Agents</h2>
+              <p className="text-xs text-muted-foreground mt-1">
+                Limit how many task agents run at once. Extra tasks wait in a queue.
+              </p>
+            </div>
+
+            <div className="bg-card border border-border rounded-lg p-4 space-y-4">
+              {/* Global limit */}
+              <div>
+                <div className="flex items-center justify-between mb-2">
+                  <div className="flex items-center gap-2">
+                    <Cpu className="w-4 h-4 text-muted-foreground" />
+                    <div>
+                      <label className="text-sm font-medium text-foreground">Parallel Agents</label>
+                      <p className="text-xs text-muted-foreground mt-0.5">
+                        Across all projects
+                      </p>
+                    </div>
+                  </div>
+                  <span className="text-sm text-muted-foreground font-mono">
+                    {medusaSettings.max_concurrent_agents}
+                  </span>
+                </div>
+                <input
+                  type="range"
+                  min={1}
+                  max={10}
+                  step={1}
+                  value={medusaSettings.max_concurrent_agents}
+                  onChange={(e) => handleMaxAgentsChange(Number(e.target.value))}
+                  disabled={savingSettings}
+                  className="w-full h-1.5 bg-muted rounded-full appearance-none cursor-pointer disabled:opacity-50
+                    [&::-webkit-slider-thumb]:appearance-none
+                    [&::-webkit-slider-thumb]:w-4
+                    [&::-webkit-slider-thumb]:h-4
+                    [&::-webkit-slider-thumb]:rounded-full
+                    [&::-webkit-slider-thumb]:bg-primary
+                    [&::-webkit-slider-thumb]:cursor-pointer
+                    [&::-moz-range-thumb]:w-4
+                    [&::-moz-range-thumb]:h-4
+                    [&::-moz-range-thumb]:rounded-full
+                    [&::-moz-range-thumb]:bg-primary
+                    [&::-moz-range-thumb]:border-0
+                    [&::-moz-range-thumb]:cursor-pointer"
+                />
+              </div>
+
+              {/* Per-project limit */}
+              <div>
+                <div className="flex items-center justify-between mb-2">
+                  <div>
+                    <label className="text-sm font-medium text-foreground">Per Project</label>
+                    <p className="text-xs text-muted-foreground mt-0.5">
+                      Agents running in the same repository
+                    </p>
+                  </div>
+                  <span className="text-sm text-muted-foreground font-mono">
+                    {medusaSettings.max_agents_per_project ?? 'No limit'}
+                  </span>
+                </div>
+                <input
+                  type="range"
+                  min={1}
+                  max={medusaSettings.max_concurrent_agents}
+                  step={1}
+                  value={medusaSettings.max_agents_per_project ?? medusaSettings.max_concurrent_agents}
+                  onChange={(e) => handleMaxAgentsPerProjectChange(Number(e.target.value))}
+                  disabled={savingSettings}
+                  className="w-full h-1.5 bg-muted rounded-full appearance-none cursor-pointer disabled:opacity-50
+                    [&::-webkit-slider-thumb]:appearance-none
+                    [&::-webkit-slider-thumb]:w-4
+                    [&::-webkit-slider-thumb]:h-4
+                    [&::-webkit-slider-thumb]:rounded-full
+                    [&::-webkit-slider-thumb]:bg-primary
+                    [&::-webkit-slider-thumb]:cursor-pointer
+                    [&::-moz-range-thumb]:w-4
+                    [&::-moz-range-thumb]:h-4
+                    [&::-moz-range-thumb]:rounded-full
+                    [&::-moz-range-thumb]:bg-primary
+                    [&::-moz-range-thumb]:border-0
+                    [&::-moz-range-thumb]:cursor-pointer"
+                />
+              </div>
+            </div>
+          </div>
+
           {/* About Section */}
           <div className="space-y-4">
             <div>
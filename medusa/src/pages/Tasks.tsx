@@ -4,7 +4,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { ArrowLeft, Plus, RefreshCw, Search, X } from 'lucide-react';
 import { Task, TaskStatus } from '../types';
-import { TaskCard, CreateTaskModal, AgentOutputModal, TaskPreviewModal } from '../components/tasks';
+import { TaskCard, CreateTaskModal, AgentOutputModal, TaskPreviewModal, NewTaskOptions } from '../components/tasks';
 import { ask } from '@tauri-apps/plugin-dialog';
 
 const COLUMNS: { status: TaskStatus; label: string; color: string }[] = [
@@ -63,10 +63,10 @@ export default function Tasks() {
     };
   }, [loadTasks]);
 
-  const handleCreateTask = async (title: string, description: string, projectPath: string) => {
+  const handleCreateTask = async (title: string, description: string, projectPath: string, options: NewTaskOptions) => {
     try {
       await invoke('create_task', {
-        request: { title, description, project_path: projectPath },
+        request: { title, description, project_path: projectPath, ...options },
       });
       setShowCreateModal(false);
       loadTasks();
@@ -182,7 +182,10 @@ export default function Tasks() {
 
   // Group tasks by status
   const tasksByStatus = COLUMNS.reduce((acc, col) => {
-    acc[col.status] = filteredTasks.filter((t) => t.status === col.status);
+    acc[col.status] = filteredTasks.filter((t) =>
+      // Queued tasks wait in the In Progress column
+      t.status === col.status || (col.status === TaskStatus.InProgress && t.status === TaskStatus.Queued)
+    );
     return acc;
   }, {} as Record<TaskStatus, Task[]>);
 
@@ -345,6 +348,7 @@ export default function Tasks() {
         <CreateTaskModal
           onClose={() => setShowCreateModal(false)}
           onCreate={handleCreateTask}
+          tasks={tasks}
         />
       )}
 
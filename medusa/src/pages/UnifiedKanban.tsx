@@ -21,11 +21,22 @@ import {
   Loader2,
   CheckCircle,
   FileCode,
+  Split,
+  Copy,
+  Columns,
 } from 'lucide-react';
-import { PlanItem, PlanStatus, Task, TaskStatus, TaskPlan } from '../types';
+import { PlanItem, PlanStatus, Task, TaskStatus, TaskPlan, LaunchProfile } from '../types';
 import { PlanCard } from '../components/kanban/PlanCard';
 import { PlanReviewModal } from '../components/kanban/PlanReviewModal';
-import { CreateTaskModal, AgentOutputModal, TaskPreviewModal } from '../components/tasks';
+import {
+  CreateTaskModal,
+  AgentOutputModal,
+  TaskPreviewModal,
+  DecomposeTaskModal,
+  StartAttemptsModal,
+  CompareAttemptsModal,
+  NewTaskOptions,
+} from '../components/tasks';
 import { HistoryPreviewModal } from '../components/history';
 import { isPermissionGranted, requestPermission, sendNotification } from '@tauri-apps/plugin-notification';
 import { ask } from '@tauri-apps/plugin-dialog';
@@ -59,6 +70,9 @@ export default function UnifiedKanban() {
   const [outputTask, setOutputTask] = useState<Task | null>(null);
   const [previewTask, setPreviewTask] = useState<Task | null>(null);
   const [previewPlan, setPreviewPlan] = useState<PlanItem | null>(null);
+  const [decomposeTask, setDecomposeTask] = useState<Task | null>(null);
+  const [attemptsTask, setAttemptsTask] = useState<Task | null>(null);
+  const [compareTask, setCompareTask] = useState<Task | null>(null);
   const [committingTaskId, setCommittingTaskId] = useState<string | null>(null);
 
   // Task plans (when agent enters plan mode within a task)
@@ -224,9 +238,9 @@ export default function UnifiedKanban() {
         break;
 
       case 'in_progress':
-        // Tasks that are in progress (with or without agent running)
+        // Tasks that are in progress (with or without agent running) or waiting in the queue
         filteredTasks
-          .filter(t => t.status === TaskStatus.InProgress)
+          .filter(t => t.status === TaskStatus.InProgress || t.status === TaskStatus.Queued)
           .forEach(t => items.push({ type: 'task', data: t }));
         // Plans that are being worked on (changes requested)
         filteredPlans
@@ -281,10 +295,10 @@ export default function UnifiedKanban() {
   };
 
   // Task handlers
-  const handleCreateTask = async (title: string, description: string, projectPath: string) => {
+  const handleCreateTask = async (title: string, description: string, projectPath: string, options: NewTaskOptions) => {
     try {
       await invoke('create_task', {
-        request: { title, description, project_path: projectPath },
+        request: { title, description, project_path: projectPath, ...options },
       });
       setShowCreateTaskModal(false);
       loadData();
@@ -331,6 +345,17 @@ export default function UnifiedKanban() {
     }
   };
 
+  const handleStartAttempts = async (task: Task, profiles: LaunchProfile[]) => {
+    try {
+      await invoke('start_task_attempts', { taskId: task.id, profiles });
+      setAttemptsTask(null);
+      loadData();
+    } catch (error) {
+      console.error('Failed to start attempts:', error);
+      alert(`Failed to start attempts: ${error}`);
+    }
+  };
+
   const handleSendToReview = async (task: Task) => {
     try {
       if (task.worktree_path) {
@@ -514,6 +539,9 @@ export default function UnifiedKanban() {
                         onStopAgent={() => handleStopAgent(task!)}
                         onSendToReview={() => handleSendToReview(task!)}
                         onViewOutput={() => setOutputTask(task!)}
+                        onDecompose={() => setDecomposeTask(task!)}
+                        onStartAttempts={() => setAttemptsTask(task!)}
+                        onCompareAttempts={() => setCompareTask(task!)}
                         onPreview={isDoneTask ? () => setPreviewTask(task!) : undefined}
                         isCommitting={committingTaskId === task!.id}
                         pendingPlan={taskPlans.get(task!.id)}
@@ -555,6 +583,7 @@ export default function UnifiedKanban() {
         <CreateTaskModal
           onClose={() => setShowCreateTaskModal(false)}
           onCreate={handleCreateTask}
+          tasks={tasks}
         />
       )}
 
@@ -572,6 +601,33 @@ export default function UnifiedKanban() {
         />
       )}
 
+      {decomposeTask && (
+        <DecomposeTaskModal
+          task={decomposeTask}
+          onClose={() => setDecomposeTask(null)}
+          onCreated={() => {
+            setDecomposeTask(null);
+            loadData();
+          }}
+        />
+      )}
+
+      {attemptsTask && (
+        <StartAttemptsModal
+          task={attemptsTask}
+          onClose={() => setAttemptsTask(null)}
+          onStart={(profiles) => handleStartAttempts(attemptsTask, profiles)}
+        />
+      )}
+
+      {compareTask && (
+        <CompareAttemptsModal
+          task={compareTask}
+          onClose={() => setCompareTask(null)}
+          onChanged={loadData}
+        />
+      )}
+
       {previewPlan && (
         <HistoryPreviewModal
           item={{
@@ -602,6 +658,9 @@ function TaskCardInline({
   onStopAgent,
   onSendToReview,
   onViewOutput,
+  onDecompose,
+  onStartAttempts,
+  onCompareAttempts,
   onPreview,
   isCommitting,
   pendingPlan,
@@ -614,6 +673,9 @@ function TaskCardInline({
   onStopAgent: () => void;
   onSendToReview: () => void;
   onViewOutput: () => void;
+  onDecompose: () => void;
+  onStartAttempts: () => void;
+  onCompareAttempts: () => void;
   onPreview?: () => void;
   isCommitting: boolean;
   pendingPlan?: TaskPlan;
@@ -645,9 +707,12 @@ function TaskCardInline({
   const projectName = task.project_path.split('/').pop() || 'Unknown';
   const isRunning = !!task.agent_pid;
   const isPaused = !task.agent_pid && !!task.session_id && task.status === TaskStatus.InProgress;
-  const isInProgressNotRunning = task.status === TaskStatus.InProgress && !task.agent_pid;
+  // A task started as parallel attempts has no worktree of its own until one is merged
+  const hasAttempts = task.status === TaskStatus.InProgress && !task.worktree_path;
+  const isInProgressNotRunning = task.status === TaskStatus.InProgress && !task.agent_pid && !hasAttempts;
   const canStart = task.status === TaskStatus.Backlog;
   const canResume = isPaused;
+  const isQueued = task.status === TaskStatus.Queued;
 
   const isDone = task.status === TaskStatus.Done;
 
@@ -676,6 +741,11 @@ function TaskCardInline({
             <Pause className="w-3 h-3" />
             Paused
           </span>
+        ) : isQueued ? (
+          <span className="flex items-center gap-1 text-xs text-muted-foreground">
+            <Clock className="w-3 h-3" />
+            Queued
+          </span>
         ) : null}
       </div>
 
@@ -725,6 +795,26 @@ function TaskCardInline({
             </button>
           )}
 
+          {canStart && !isRunning && (
+            <button
+              onClick={(e) => { e.stopPropagation(); onDecompose(); }}
+              className="p-1.5 text-muted-foreground hover:text-primary hover:bg-primary/10 rounded transition-colors"
+              title="Split into Subtasks"
+            >
+              <Split className="w-3.5 h-3.5" />
+            </button>
+          )}
+
+          {canStart && !isRunning && (
+            <button
+              onClick={(e) => { e.stopPropagation(); onStartAttempts(); }}
+              className="p-1.5 text-muted-foreground hover:text-primary hover:bg-primary/10 rounded transition-colors"
+              title="Run Parallel Attempts"
+            >
+              <Copy className="w-3.5 h-3.5" />
+            </button>
+          )}
+
           {canResume && (
             <button
               onClick={(e) => { e.stopPropagation(); onStartAgent(); }}
@@ -735,11 +825,11 @@ function TaskCardInline({
             </button>
           )}
 
-          {isRunning && (
+          {(isRunning || isQueued) && (
             <button
               onClick={(e) => { e.stopPropagation(); onStopAgent(); }}
               className="p-1.5 text-muted-foreground hover:text-muted-foreground/80 hover:bg-muted rounded transition-colors"
-              title="Pause Agent"
+              title={isQueued ? 'Remove from Queue' : 'Pause Agent'}
             >
               <Pause className="w-3.5 h-3.5" />
             </button>
@@ -856,6 +946,19 @@ function TaskCardInline({
         </div>
       )}
 
+      {/* Compare parallel attempts */}
+      {hasAttempts && (
+        <div className="mt-3 pt-3 border-t border-border">
+          <button
+            onClick={(e) => { e.stopPropagation(); onCompareAttempts(); }}
+            className="w-full flex items-center justify-center gap-1.5 px-3 py-1.5 text-xs font-medium bg-primary/10 text-primary rounded-lg hover:bg-primary/20 transition-colors"
+          >
+            <Columns className="w-3 h-3" />
+            Compare Attempts
+          </button>
+        </div>
+      )}
+
       {/* Send to Review button */}
       {!isCommitting && isInProgressNotRunning && !pendingPlan && (
         <div className="mt-3 pt-3 border-t border-border">
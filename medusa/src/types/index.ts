@@ -119,6 +119,7 @@ export interface AuthorIdentity {
 // Task Management types for Medusa 2.0
 export enum TaskStatus {
   Backlog = 'Backlog',
+  Queued = 'Queued', // Waiting for a free agent slot
   InProgress = 'InProgress',
   Review = 'Review',
   Done = 'Done',
@@ -139,8 +140,14 @@ export interface Task {
   // Execution tracking
   agent_pid?: number;
   session_id?: string;  // Claude Code session ID for resuming
+  agent_backend?: string; // Agent backend the task runs with ("claude" or "command")
   base_commit?: string; // The commit the worktree was created from (for accurate diffs)
   base_branch?: string; // The branch the task was created from (for merging back)
+  priority: number; // Higher priorities leave the run queue first
+  queued_at?: number; // When the task entered the run queue
+  depends_on: string[]; // Prerequisite task IDs that must be merged before this task starts
+  branch_from_dependency: boolean; // Start from a prerequisite's task branch once it's in review
+  parent_id?: string; // The task this one was split out of
   started_at?: number;
   completed_at?: number;
 
@@ -153,6 +160,60 @@ export interface Task {
   updated_at: number;
 }
 
+// Agent-proposed breakdown of a task into subtasks
+export interface SubtaskProposal {
+  title: string;
+  description: string;
+  depends_on: number[]; // Indexes of the proposals this one depends on
+}
+
+export type DecompositionStatus = 'Running' | 'Ready' | 'Failed';
+
+export interface TaskDecomposition {
+  task_id: string;
+  status: DecompositionStatus;
+  subtasks: SubtaskProposal[];
+  raw_output?: string; // The agent's answer as written, for breakdowns that couldn't be parsed
+  error?: string;
+  created_at: number;
+}
+
+// Per-run agent settings (e.g. for one of several attempts at a task)
+export interface LaunchProfile {
+  backend?: string; // Unset: the task's or the default backend
+  model?: string;
+  extra_args: string[];
+}
+
+// One of several parallel attempts at a task
+export interface TaskAttempt {
+  id: string; // Agent ID of the attempt ("<task id>--<label>")
+  task_id: string;
+  label: string; // "a", "b", ...
+  profile: LaunchProfile;
+  backend: string;
+  branch: string;
+  worktree_path: string;
+  base_commit: string;
+  base_branch: string;
+  agent_pid?: number;
+  started_at: number;
+}
+
+export interface DiffStats {
+  files_changed: number;
+  insertions: number;
+  deletions: number;
+}
+
+export interface AttemptComparison {
+  attempt: TaskAttempt;
+  status?: string; // Live agent status (unset when the agent isn't tracked)
+  diff: DiffStats;
+  changed_files: string[];
+  cost_usd?: number;
+}
+
 // Commit information for task branch
 export interface TaskCommit {
   hash: string;
@@ -176,6 +237,35 @@ export interface SetupStatus {
 // Medusa application settings
 export interface MedusaSettings {
   hook_timeout_minutes: number;
+  agent_backend: string; // Default agent backend for new tasks ("claude" or "command")
+  agent_command?: string; // Shell command for the "command" backend ({prompt} placeholder optional)
+  max_concurrent_agents: number; // Agents running at once across all projects
+  max_agents_per_project?: number; // Agents running at once per project (unset: only the global limit)
+  project_agent_limits: Record<string, number>; // Per-project overrides, keyed by project path
+}
+
+// Startup reconciliation report
+export type HealthIssueKind =
+  | 'DeadAgent'
+  | 'MissingWorktree'
+  | 'StaleWorktreeMetadata'
+  | 'OrphanWorktree'
+  | 'OrphanBranch'
+  | 'OrphanSession';
+
+export interface HealthIssue {
+  kind: HealthIssueKind;
+  task_id?: string;
+  project_path?: string;
+  path?: string;
+  message: string;
+  fixed: boolean; // Fixed automatically; unfixed issues need the user's attention
+}
+
+export interface HealthReport {
+  checked_at: number;
+  tasks_checked: number;
+  issues: HealthIssue[];
 }
 
 // Task plan pending review (when agent enters plan mode)